@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use snafu::{Backtrace, IntoError, Snafu};
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum AocError {
+    #[snafu(display("Could not read input {}: {source}", path.display()))]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("{}line {line}, column {column}: {message}", day_prefix(*day)))]
+    Parse {
+        day: Option<u8>,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[snafu(display("{}invalid puzzle state: {message}", day_prefix(*day)))]
+    InvalidState { day: Option<u8>, message: String },
+
+    #[snafu(display("{}arithmetic overflow in {operation}", day_prefix(*day)))]
+    Overflow { day: Option<u8>, operation: String },
+}
+
+pub type Result<T, E = AocError> = std::result::Result<T, E>;
+
+fn day_prefix(day: Option<u8>) -> String {
+    day.map_or(String::new(), |day| format!("day {day}: "))
+}
+
+impl AocError {
+    // lines and columns are 1-based, columns count characters
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+        AocError::Parse {
+            day: None,
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    // a parse error at byte `offset` into `text`
+    pub fn parse_at(text: &str, offset: usize, message: impl Into<String>) -> Self {
+        let offset = offset.min(text.len());
+        let before = &text[..text.floor_char_boundary(offset)];
+        let line_start = before.rfind('\n').map_or(0, |ix| ix + 1);
+
+        AocError::parse(
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+            message,
+        )
+    }
+
+    // a parse error at the start of `fragment`, which must be a slice of `text`
+    // (such as the unparsed rest of it)
+    pub fn parse_in(text: &str, fragment: &str, message: impl Into<String>) -> Self {
+        let offset = (fragment.as_ptr() as usize).saturating_sub(text.as_ptr() as usize);
+        AocError::parse_at(text, offset, message)
+    }
+
+    // a failed read, `path` names the file or stream being read
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        IoSnafu { path: path.into() }.into_error(source)
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        AocError::InvalidState {
+            day: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn overflow(operation: impl Into<String>) -> Self {
+        AocError::Overflow {
+            day: None,
+            operation: operation.into(),
+        }
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        match self {
+            AocError::Io { .. } => None,
+            AocError::Parse { day, .. }
+            | AocError::InvalidState { day, .. }
+            | AocError::Overflow { day, .. } => *day,
+        }
+    }
+
+    // tags the error with the puzzle day, keeping any day already set
+    pub fn with_day(mut self, puzzle_day: u8) -> Self {
+        if let AocError::Parse { day, .. }
+        | AocError::InvalidState { day, .. }
+        | AocError::Overflow { day, .. } = &mut self
+        {
+            day.get_or_insert(puzzle_day);
+        }
+        self
+    }
+
+    // moves a parse error found within a single line onto that line of the whole input
+    pub fn on_line(mut self, input_line: usize) -> Self {
+        if let AocError::Parse { line, .. } = &mut self {
+            *line = input_line;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_positions() {
+        let text = "ab\ncdé\nfg\n";
+
+        let err = AocError::parse_at(text, 0, "start");
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 1,
+                column: 1,
+                ..
+            }
+        ));
+
+        let err = AocError::parse_in(text, &text[7..], "after é");
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 4,
+                ..
+            }
+        ));
+
+        let err = AocError::parse_at(text, 100, "past the end");
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 4,
+                column: 1,
+                ..
+            }
+        ));
+
+        let err = AocError::parse(1, 3, "bad").on_line(7);
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 7,
+                column: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn display_with_day() {
+        let err = AocError::invalid("loop is broken").with_day(10).with_day(3);
+        assert_eq!(err.day(), Some(10));
+        assert_eq!(
+            err.to_string(),
+            "day 10: invalid puzzle state: loop is broken"
+        );
+
+        let err = AocError::parse(2, 5, "expected a digit");
+        assert_eq!(err.to_string(), "line 2, column 5: expected a digit");
+
+        let err = AocError::overflow("calibration total").with_day(1);
+        assert_eq!(
+            err.to_string(),
+            "day 1: arithmetic overflow in calibration total"
+        );
+    }
+}
@@ -0,0 +1,405 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use winnow::ascii::multispace0;
+use winnow::combinator::terminated;
+
+use crate::error::{AocError, Result};
+use crate::parse;
+
+// (row, col)
+pub type Point = (usize, usize);
+pub type Delta = (isize, isize);
+
+pub const UP: Delta = (-1, 0);
+pub const RIGHT: Delta = (0, 1);
+pub const DOWN: Delta = (1, 0);
+pub const LEFT: Delta = (0, -1);
+
+pub const NEIGHBOURS4: [Delta; 4] = [UP, RIGHT, DOWN, LEFT];
+
+// clockwise, starting straight up
+pub const NEIGHBOURS8: [Delta; 8] = [UP, (-1, 1), RIGHT, (1, 1), DOWN, (1, -1), LEFT, (-1, -1)];
+
+// Rectangular grid stored row-major in one flat Vec. Every accessor is bounds
+// checked; indexing with `grid[(row, col)]` panics like a slice would.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Result<Self> {
+        if width == 0 {
+            if !cells.is_empty() {
+                return Err(AocError::invalid(format!(
+                    "Grid of width 0 cannot hold {} cells",
+                    cells.len()
+                )));
+            }
+            return Ok(Grid {
+                width,
+                height: 0,
+                cells,
+            });
+        }
+
+        if !cells.len().is_multiple_of(width) {
+            return Err(AocError::invalid(format!(
+                "{} cells do not fill rows of width {width}",
+                cells.len()
+            )));
+        }
+
+        Ok(Grid {
+            width,
+            height: cells.len() / width,
+            cells,
+        })
+    }
+
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Self> {
+        let mut width = None;
+        let mut cells = vec![];
+
+        for (ix, row) in rows.into_iter().enumerate() {
+            match width {
+                None => width = Some(row.len()),
+                Some(width) if width != row.len() => {
+                    return Err(AocError::invalid(format!(
+                        "Grid row {} has {} columns, expected {width}",
+                        ix + 1,
+                        row.len()
+                    )))
+                }
+                _ => {}
+            }
+            cells.extend(row);
+        }
+
+        Grid::from_vec(width.unwrap_or(0), cells)
+    }
+
+    // one cell per character, every line must be the same length
+    pub fn parse_with(text: &str, cell: impl FnMut(char) -> T) -> Result<Self> {
+        parse::run(text, terminated(parse::grid(cell), multispace0))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (row, col): Point) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point).then(|| point.0 * self.width + point.1)
+    }
+
+    pub fn point_of(&self, index: usize) -> Option<Point> {
+        (index < self.cells.len()).then(|| (index / self.width, index % self.width))
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|ix| &self.cells[ix])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|ix| &mut self.cells[ix])
+    }
+
+    // the point one step of `delta` away, if it is still on the grid
+    pub fn offset(&self, (row, col): Point, (d_row, d_col): Delta) -> Option<Point> {
+        let point = (
+            row.checked_add_signed(d_row)?,
+            col.checked_add_signed(d_col)?,
+        );
+
+        self.contains(point).then_some(point)
+    }
+
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS4
+            .iter()
+            .filter_map(move |&delta| self.offset(point, delta))
+    }
+
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS8
+            .iter()
+            .filter_map(move |&delta| self.offset(point, delta))
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks_exact panics on a zero chunk size; a zero width grid has no cells anyway
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> Option<impl Iterator<Item = &T>> {
+        (col < self.width).then(|| self.cells[col..].iter().step_by(self.width))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |col| self.cells[col..].iter().step_by(self.width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(ix, cell)| ((ix / self.width, ix % self.width), cell))
+    }
+
+    pub fn values(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .and_then(|ix| self.point_of(ix))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    // builds a grid of the given size whose cell at every point is `f(point)`
+    fn generate(width: usize, height: usize, f: impl Fn(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(f)
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::generate(self.height, self.width, |(row, col)| {
+            self[(col, row)].clone()
+        })
+    }
+
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::generate(self.height, self.width, |(row, col)| {
+            self[(self.height - 1 - col, row)].clone()
+        })
+    }
+
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::generate(self.height, self.width, |(row, col)| {
+            self[(col, self.width - 1 - row)].clone()
+        })
+    }
+
+    // mirror left to right
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::generate(self.width, self.height, |(row, col)| {
+            self[(row, self.width - 1 - col)].clone()
+        })
+    }
+
+    // mirror top to bottom
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::generate(self.width, self.height, |(row, col)| {
+            self[(self.height - 1 - row, col)].clone()
+        })
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.get(point) {
+            Some(cell) => cell,
+            None => panic!(
+                "point {point:?} is outside a {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(point) {
+            Some(cell) => cell,
+            None => panic!("point {point:?} is outside a {width}x{height} grid"),
+        }
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Grid::parse_with(s, |c| c)
+    }
+}
+
+impl FromStr for Grid<u8> {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(ix) = s.find(|c: char| !c.is_ascii()) {
+            return Err(AocError::parse_at(s, ix, "Byte grid must be ASCII"));
+        }
+
+        Grid::parse_with(s, |c| c as u8)
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Grid<u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        "abc\ndef\n".parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_index() {
+        let grid = sample();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.position(|&c| c == 'e'), Some((1, 1)));
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+
+        let err = "ab\nc".parse::<Grid<char>>().unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 2,
+                ..
+            }
+        ));
+        assert!("".parse::<Grid<char>>().is_err());
+        assert!(Grid::from_vec(2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = sample();
+
+        let corner: Vec<Point> = grid.neighbours4((0, 0)).collect();
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+
+        let middle: Vec<char> = grid.neighbours8((0, 1)).map(|p| grid[p]).collect();
+        assert_eq!(middle, vec!['c', 'f', 'e', 'd', 'a']);
+
+        assert_eq!(grid.offset((1, 2), RIGHT), None);
+        assert_eq!(grid.offset((1, 2), UP), Some((0, 2)));
+    }
+
+    #[test]
+    fn views() {
+        let grid = sample();
+
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(2).unwrap().collect::<String>(), "cf".to_owned());
+        assert!(grid.column(3).is_none());
+
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, vec!["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = sample();
+
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.transpose().transpose(), grid);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let grid = sample();
+        let _ = grid[(0, 3)];
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+use snafu::ResultExt;
+
+use crate::error::{IoSnafu, Result};
+use crate::grid::Grid;
+
+// Puzzle input with its line endings normalized: `\r\n` becomes `\n`, a leading
+// byte order mark is dropped, trailing blank lines are trimmed and every
+// non-empty input ends with exactly one `\n`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    pub fn new(raw: &str) -> Self {
+        let raw = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+
+        let mut lines: Vec<&str> = raw.lines().collect();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+
+        let mut text = String::with_capacity(raw.len() + 1);
+        for line in lines {
+            text.push_str(line);
+            text.push('\n');
+        }
+
+        Input { text }
+    }
+
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path).context(IoSnafu { path })?;
+
+        Ok(Input::new(&raw))
+    }
+
+    pub fn from_reader(reader: impl Read) -> Result<Self> {
+        Input::read_named(reader, "<reader>")
+    }
+
+    pub fn from_stdin() -> Result<Self> {
+        Input::read_named(std::io::stdin().lock(), "<stdin>")
+    }
+
+    // `name` stands in for the path in I/O errors
+    fn read_named(mut reader: impl Read, name: &str) -> Result<Self> {
+        let mut raw = String::new();
+        reader
+            .read_to_string(&mut raw)
+            .context(IoSnafu { path: name })?;
+
+        Ok(Input::new(&raw))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.text.as_bytes()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.text.lines()
+    }
+
+    // groups of lines separated by one or more blank (or whitespace only) lines,
+    // without their final newline
+    pub fn blocks(&self) -> impl Iterator<Item = &str> {
+        let mut spans = vec![];
+        let mut block_start = None;
+        let mut offset = 0;
+
+        for line in self.text.split_inclusive('\n') {
+            match (line.trim().is_empty(), block_start) {
+                (false, None) => block_start = Some(offset),
+                (true, Some(start)) => {
+                    spans.push(start..offset - 1);
+                    block_start = None;
+                }
+                _ => {}
+            }
+            offset += line.len();
+        }
+
+        if let Some(start) = block_start {
+            spans.push(start..self.text.len() - 1);
+        }
+
+        spans.into_iter().map(|span| &self.text[span])
+    }
+
+    // one cell per character; every row must be as wide as the first
+    pub fn char_grid(&self) -> Result<Grid<char>> {
+        self.text.parse()
+    }
+
+    pub fn byte_grid(&self) -> Result<Grid<u8>> {
+        self.text.parse()
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+impl From<&str> for Input {
+    fn from(raw: &str) -> Self {
+        Input::new(raw)
+    }
+}
+
+impl From<String> for Input {
+    fn from(raw: String) -> Self {
+        Input::new(&raw)
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AocError;
+
+    #[test]
+    fn normalizes_line_endings() {
+        let input = Input::new("\u{feff}ab\r\ncd\r\n\r\n\r\n");
+        assert_eq!(input.as_str(), "ab\ncd\n");
+
+        assert_eq!(Input::new("ab\ncd").as_str(), "ab\ncd\n");
+        assert_eq!(Input::new("ab\ncd\n").as_str(), "ab\ncd\n");
+        assert_eq!(Input::new("\n  \n").as_str(), "");
+        assert!(Input::new("").is_empty());
+    }
+
+    #[test]
+    fn blocks() {
+        let input = Input::new("\n#.\n.#\r\n\r\n##\n..\n  \n\n#\n");
+        let blocks: Vec<&str> = input.blocks().collect();
+
+        assert_eq!(blocks, vec!["#.\n.#", "##\n..", "#"]);
+    }
+
+    #[test]
+    fn grids() {
+        let grid = Input::new("ab\r\ncd\r\n").char_grid().unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid[(1, 0)], 'c');
+
+        assert!(Input::new("ab\nc\n").char_grid().is_err());
+        assert!(Input::new("").char_grid().is_err());
+        assert!(Input::new("ab\nc\u{e9}\n").byte_grid().is_err());
+    }
+
+    #[test]
+    fn io_errors() {
+        let missing = Input::from_path("src/fixtures/day0.txt").unwrap_err();
+        assert!(format!("{missing:#}").contains("day0.txt"));
+
+        let invalid: &[u8] = &[0x61, 0xff, 0x0a];
+        let err = Input::from_reader(invalid).unwrap_err();
+        assert!(matches!(err, AocError::Io { .. }));
+        assert!(err.to_string().contains("<reader>"));
+    }
+}
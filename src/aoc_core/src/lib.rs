@@ -0,0 +1,11 @@
+// Pieces shared by every event year: puzzle input and errors, the solution
+// registry the runner drives, and the grid, parser, trie and sort helpers the
+// days are built from.
+
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod lsd_sort;
+pub mod parse;
+pub mod solution;
+pub mod trie;
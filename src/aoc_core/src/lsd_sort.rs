@@ -17,17 +17,17 @@ with a map from a provided alpha to normalized alpha sort order.
 i.e. If you wanted a lexical order of T, U, A vs. the normal A, T, U...
 a map of T->A, U-> B, A->C would accomplish this.
 */
-use super::Hand;
 
-fn get_object_str_repr(hand: Hand) -> String {
-    hand.cards
-}
-
-pub(crate) fn sort_hands(mut array: &mut Vec<Hand>, override_sort: Option<&HashMap<char, char>>) {
+// sorts items by a fixed-length ASCII key, such as a hand of cards
+pub fn sort_by_key<T: Default + Clone>(
+    mut array: &mut Vec<T>,
+    key: impl Fn(&T) -> &str,
+    override_sort: Option<&HashMap<char, char>>,
+) {
     let R = 256;
-    let n_char = array[0].cards.len();
+    let n_char = key(&array[0]).len();
     let n_str = array.len();
-    let mut aux: Vec<Hand> = vec![Hand::default(); n_str];
+    let mut aux: Vec<T> = vec![T::default(); n_str];
 
     let mut pt_aux = aux.as_mut_ptr();
     let mut pt_arr = array.as_mut_ptr();
@@ -36,13 +36,17 @@ pub(crate) fn sort_hands(mut array: &mut Vec<Hand>, override_sort: Option<&HashM
     for c in (0..n_char).rev() {
         let mut counts = vec![0usize; R + 1];
 
+        // get any overriden index for this the letters in this slot
+        let slots: Vec<usize> = array
+            .iter()
+            .map(|item| {
+                let b = key(item).as_bytes()[c] as usize;
+                override_sort.map_or(b + 1, |map| check_override_mapping(map, b))
+            })
+            .collect();
+
         // compute frequency counts for this letter slot for all strings
-        for f in 0..n_str {
-            // get any overriden index for this the letters in this slot
-            let mapped_idx = override_sort.as_ref().map_or_else(
-                || array[f].cards.clone().into_bytes()[c] as usize + 1,
-                |map| check_override_mapping(&map, array[f].cards.clone().into_bytes()[c] as usize),
-            );
+        for &mapped_idx in &slots {
             counts[mapped_idx + 1] += 1;
         }
 
@@ -52,11 +56,7 @@ pub(crate) fn sort_hands(mut array: &mut Vec<Hand>, override_sort: Option<&HashM
         }
 
         // move each string into aux in order
-        for m in 0..n_str {
-            let mapped_idx = override_sort.as_ref().map_or_else(
-                || array[m].cards.clone().into_bytes()[c] as usize + 1,
-                |map| check_override_mapping(&map, array[m].cards.clone().into_bytes()[c] as usize),
-            );
+        for (m, &mapped_idx) in slots.iter().enumerate() {
             unsafe {
                 std::ptr::swap(pt_arr.add(m), pt_aux.add(counts[mapped_idx]));
             }
@@ -72,10 +72,7 @@ pub(crate) fn sort_hands(mut array: &mut Vec<Hand>, override_sort: Option<&HashM
     }
 }
 
-pub(crate) fn sort(
-    mut array: Vec<String>,
-    override_sort: Option<HashMap<char, char>>,
-) -> Vec<String> {
+pub fn sort(mut array: Vec<String>, override_sort: Option<HashMap<char, char>>) -> Vec<String> {
     // let R = 256;
     let R = 100;
     let mut aux: Vec<Vec<u8>> = vec![vec![0; 1]; R + 1];
@@ -87,16 +84,19 @@ pub(crate) fn sort(
     for c in (0..n_char).rev() {
         let mut counts = vec![0usize; R + 1];
 
-        // compute frequency counts for this letter slot for all strings
-        for f in 0..n_str {
-            // get any overriden index for this the letters in this slot
-            // dbg!(&array[f]);
-
-            let mapped_idx = override_sort.as_ref().map_or_else(
-                || array[f][c] as usize + 1,
-                |map| check_override_mapping(&map, array[f][c] as usize), //+ 1),
-            );
+        // get any overriden index for this the letters in this slot
+        let slots: Vec<usize> = array
+            .iter()
+            .map(|word| {
+                let b = word[c] as usize;
+                override_sort
+                    .as_ref()
+                    .map_or(b + 1, |map| check_override_mapping(map, b)) //+ 1),
+            })
+            .collect();
 
+        // compute frequency counts for this letter slot for all strings
+        for &mapped_idx in &slots {
             // println!(
             //     "Overview: idx: {}, c_index:{}, word:{:?}",
             //     mapped_idx as u8 as char,
@@ -119,13 +119,8 @@ pub(crate) fn sort(
         // println!("Transform: {:?}", &counts);
 
         // move each string into aux in order
-        for m in 0..n_str {
-            // let idx = array[m][c] as usize;
-            // println!("Aux ptr add: {}, Arr ptr add: {}", counts[idx], m);
-            let mapped_idx = override_sort.as_ref().map_or_else(
-                || array[m][c] as usize + 1,
-                |map| check_override_mapping(&map, array[m][c] as usize), //+ 1),
-            );
+        for (m, &mapped_idx) in slots.iter().enumerate() {
+            // println!("Aux ptr add: {}, Arr ptr add: {}", counts[mapped_idx], m);
 
             unsafe {
                 // println!("pt add: {}, at add: {}", counts[mapped_idx], m);
@@ -173,15 +168,15 @@ mod tests {
     #[test]
     fn lsd_sort_with_override() {
         let mut override_sort: HashMap<char, char> = HashMap::new();
-        override_sort.insert('3' as char, 'Z' as char);
-        override_sort.insert('T' as char, 'M' as char);
+        override_sort.insert('3', 'Z');
+        override_sort.insert('T', 'M');
 
         let input = vec!["32T3K", "T55J5", "KK677", "KTJJT", "QQQJA"];
         let input = input.into_iter().map(|s| s.to_string()).collect();
 
         let output = sort(input, Some(override_sort));
 
-        let sorted = vec!["KK677", "KTJJT", "T55J5", "QQQJA", "32T3K"];
+        let sorted = ["KK677", "KTJJT", "T55J5", "QQQJA", "32T3K"];
         let sorted: Vec<String> = sorted.iter().map(|s| s.to_string()).collect();
 
         assert_eq!(sorted, output);
@@ -194,7 +189,7 @@ mod tests {
 
         let output = sort(input, None);
 
-        let sorted = vec!["32T3K", "KK677", "KTJJT", "QQQJA", "T55J5"];
+        let sorted = ["32T3K", "KK677", "KTJJT", "QQQJA", "T55J5"];
         let sorted: Vec<String> = sorted.iter().map(|s| s.to_string()).collect();
 
         assert_eq!(sorted, output);
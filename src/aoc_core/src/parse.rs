@@ -0,0 +1,348 @@
+use std::str::FromStr;
+
+use winnow::ascii::{digit1, line_ending, space0};
+use winnow::combinator::{alt, cut_err, eof, opt, preceded, repeat, terminated};
+use winnow::error::{
+    AddContext, ContextError, ErrMode, ErrorKind, ParserError, StrContext, StrContextValue,
+};
+use winnow::stream::Stream;
+use winnow::token::{take, take_till, take_while};
+use winnow::{PResult, Parser};
+
+use crate::error::{AocError, Result};
+use crate::grid::Grid;
+
+// Shared winnow combinators for puzzle inputs. Every parser works on `&mut &str`
+// and `run`/`run_lines` turn a failure into an `AocError::Parse` pointing at the
+// line and column where the input stopped making sense.
+
+// parses all of `text`, failing unless the parser consumes it entirely
+pub fn run<'a, O>(text: &'a str, mut parser: impl Parser<&'a str, O, ContextError>) -> Result<O> {
+    terminated(parser.by_ref(), eof)
+        .parse(text)
+        .map_err(|e| AocError::parse_at(text, e.offset(), message(e.inner())))
+}
+
+// parses every line of `text` on its own, reporting failures on that line
+pub fn run_lines<'a, O>(
+    text: &'a str,
+    mut parser: impl Parser<&'a str, O, ContextError>,
+) -> Result<Vec<O>> {
+    text.lines()
+        .enumerate()
+        .map(|(ix, line)| run(line, parser.by_ref()).map_err(|e| e.on_line(ix + 1)))
+        .collect()
+}
+
+fn message(error: &ContextError) -> String {
+    let message = error.to_string().replace('\n', "; ");
+    match message.is_empty() {
+        true => "unexpected input".to_owned(),
+        false => message,
+    }
+}
+
+// a context label ("invalid <label>") for `.context(...)`
+pub fn label(name: &'static str) -> StrContext {
+    StrContext::Label(name)
+}
+
+// what should have been there instead ("expected <description>")
+pub fn expected(description: &'static str) -> StrContext {
+    StrContext::Expected(StrContextValue::Description(description))
+}
+
+pub fn unsigned<T: FromStr>(input: &mut &str) -> PResult<T> {
+    digit1.parse_to().context(label("number")).parse_next(input)
+}
+
+pub fn signed<T: FromStr>(input: &mut &str) -> PResult<T> {
+    (opt(alt(('-', '+'))), digit1)
+        .recognize()
+        .parse_to()
+        .context(label("number"))
+        .parse_next(input)
+}
+
+// one or more items separated (and optionally surrounded) by spaces or tabs
+pub fn spaced<'a, O>(
+    item: impl Parser<&'a str, O, ContextError>,
+) -> impl Parser<&'a str, Vec<O>, ContextError> {
+    terminated(repeat(1.., preceded(space0, item)), space0)
+}
+
+pub fn unsigned_list<T: FromStr>(input: &mut &str) -> PResult<Vec<T>> {
+    spaced(unsigned).parse_next(input)
+}
+
+pub fn signed_list<T: FromStr>(input: &mut &str) -> PResult<Vec<T>> {
+    spaced(signed).parse_next(input)
+}
+
+// one or more items separated by `separator`; an item missing after a separator
+// is an error at that point rather than the end of the list
+pub fn separated_by<'a, O>(
+    mut item: impl Parser<&'a str, O, ContextError>,
+    separator: char,
+) -> impl Parser<&'a str, Vec<O>, ContextError> {
+    move |input: &mut &'a str| {
+        let mut items = vec![item.parse_next(input)?];
+        while opt((space0, separator, space0))
+            .parse_next(input)?
+            .is_some()
+        {
+            items.push(cut_err(item.by_ref()).parse_next(input)?);
+        }
+        Ok(items)
+    }
+}
+
+pub fn comma_list<T: FromStr>(input: &mut &str) -> PResult<Vec<T>> {
+    separated_by(unsigned, ',').parse_next(input)
+}
+
+pub fn name<'a>(input: &mut &'a str) -> PResult<&'a str> {
+    take_while(1.., |c: char| c.is_alphanumeric())
+        .context(label("name"))
+        .parse_next(input)
+}
+
+// "AAA = (BBB, CCC)"
+pub fn key_pair<'a>(input: &mut &'a str) -> PResult<(&'a str, (&'a str, &'a str))> {
+    let key = terminated(name, (space0, '=', space0)).parse_next(input)?;
+    let pair = cut_err((
+        preceded(('(', space0), name),
+        preceded((space0, ',', space0), name),
+    ))
+    .parse_next(input)?;
+    cut_err((space0, ')')).parse_next(input)?;
+
+    Ok((key, pair))
+}
+
+// "Time:  7  15  30" with `labelled("Time:", unsigned_list)`
+pub fn labelled<'a, O>(
+    header: &'static str,
+    parser: impl Parser<&'a str, O, ContextError>,
+) -> impl Parser<&'a str, O, ContextError> {
+    preceded((header.context(label(header)), space0), parser)
+}
+
+// the parser followed by the end of its line
+pub fn line<'a, O>(
+    parser: impl Parser<&'a str, O, ContextError>,
+) -> impl Parser<&'a str, O, ContextError> {
+    terminated(parser, alt((line_ending, eof)))
+}
+
+// one or more non-blank lines, each of which must match `item` completely; stops
+// at a blank line or the end of the input
+pub fn lines<'a, O>(
+    mut item: impl Parser<&'a str, O, ContextError>,
+) -> impl Parser<&'a str, Vec<O>, ContextError> {
+    move |input: &mut &'a str| {
+        let mut items = vec![];
+        loop {
+            let rest = input.split(['\n', '\r']).next().unwrap_or_default();
+            if rest.trim().is_empty() {
+                break;
+            }
+            items.push(cut_err(line(item.by_ref())).parse_next(input)?);
+        }
+
+        match items.is_empty() {
+            true => Err(ErrMode::Backtrack(
+                ContextError::from_error_kind(input, ErrorKind::Many)
+                    .add_context(input, expected("a line")),
+            )),
+            false => Ok(items),
+        }
+    }
+}
+
+// one or more blank (or whitespace only) lines
+pub fn blank_lines(input: &mut &str) -> PResult<()> {
+    repeat(1.., (space0, line_ending)).parse_next(input)
+}
+
+// blocks of lines separated by blank lines
+pub fn blocks<'a, O>(
+    mut block: impl Parser<&'a str, O, ContextError>,
+) -> impl Parser<&'a str, Vec<O>, ContextError> {
+    move |input: &mut &'a str| {
+        let _ = opt(blank_lines).parse_next(input)?;
+        let mut items = vec![block.parse_next(input)?];
+        while opt(blank_lines).parse_next(input)?.is_some() && !input.is_empty() {
+            items.push(block.parse_next(input)?);
+        }
+        Ok(items)
+    }
+}
+
+// a rectangle of one cell per character, rows ending at a line break or a blank
+// line, every row as wide as the first
+pub fn grid<'a, T>(mut cell: impl FnMut(char) -> T) -> impl Parser<&'a str, Grid<T>, ContextError> {
+    move |input: &mut &'a str| {
+        let mut width = None;
+        let mut cells = vec![];
+
+        loop {
+            let start = input.checkpoint();
+            let row = take_till(0.., ['\n', '\r']).parse_next(input)?;
+            if row.trim().is_empty() {
+                input.reset(start);
+                break;
+            }
+
+            let len = row.chars().count();
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    input.reset(start);
+                    let _ = take(width.min(len)).parse_next(input)?;
+                    return Err(ErrMode::Cut(
+                        ContextError::from_error_kind(input, ErrorKind::Verify)
+                            .add_context(input, label("grid row"))
+                            .add_context(input, expected("every row as wide as the first")),
+                    ));
+                }
+                _ => {}
+            }
+            cells.extend(row.chars().map(&mut cell));
+
+            if opt(line_ending).parse_next(input)?.is_none() {
+                break;
+            }
+        }
+
+        if cells.is_empty() {
+            return Err(ErrMode::Backtrack(
+                ContextError::from_error_kind(input, ErrorKind::Verify)
+                    .add_context(input, label("grid")),
+            ));
+        }
+
+        Grid::from_vec(width.unwrap_or(0), cells)
+            .map_err(|_| ErrMode::Cut(ContextError::from_error_kind(input, ErrorKind::Verify)))
+    }
+}
+
+pub fn char_grid(input: &mut &str) -> PResult<Grid<char>> {
+    grid(|c| c).parse_next(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(run("42", unsigned::<usize>).unwrap(), 42);
+        assert_eq!(run("-42", signed::<isize>).unwrap(), -42);
+        assert_eq!(
+            run("  0 3\t-6  ", signed_list::<i64>).unwrap(),
+            vec![0, 3, -6]
+        );
+        assert_eq!(run("1,2, 3", comma_list::<u8>).unwrap(), vec![1, 2, 3]);
+
+        let err = run("0 3 x 9", signed_list::<i64>).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 1,
+                column: 5,
+                ..
+            }
+        ));
+
+        let err = run("1,2,", comma_list::<usize>).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 1,
+                column: 5,
+                ..
+            }
+        ));
+
+        assert!(run("300", unsigned::<u8>).is_err());
+    }
+
+    #[test]
+    fn pairs_and_headers() {
+        assert_eq!(
+            run("AAA = (BBB, 11Z)", key_pair).unwrap(),
+            ("AAA", ("BBB", "11Z"))
+        );
+
+        let err = run("AAA = (BBB CCC)", key_pair).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 1,
+                column: 12,
+                ..
+            }
+        ));
+
+        let times = run("Time:  7  15  30", labelled("Time:", unsigned_list::<u32>));
+        assert_eq!(times.unwrap(), vec![7, 15, 30]);
+
+        let err = run("Tim: 7", labelled("Time:", unsigned_list::<u32>)).unwrap_err();
+        assert!(err.to_string().contains("Time:"));
+    }
+
+    #[test]
+    fn lines_and_blocks() {
+        let rows = run_lines("1 2\n3 4\n5 x\n", unsigned_list::<usize>);
+        assert!(matches!(
+            rows,
+            Err(AocError::Parse {
+                line: 3,
+                column: 3,
+                ..
+            })
+        ));
+
+        let nodes = run("AAA = (B, C)\nBBB = (C, D)\n", lines(key_pair)).unwrap();
+        assert_eq!(nodes[1], ("BBB", ("C", "D")));
+
+        let err = run("AAA = (B, C)\nBBB (C, D)\n", lines(key_pair)).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 5,
+                ..
+            }
+        ));
+
+        let both = run(
+            "seeds: 1 2\n",
+            line(labelled("seeds:", unsigned_list::<usize>)),
+        );
+        assert_eq!(both.unwrap(), vec![1, 2]);
+
+        let grids = run("#.\n.#\n  \n\n##\n..\n", blocks(char_grid)).unwrap();
+        assert_eq!(grids.len(), 2);
+        assert_eq!(grids[1].to_string(), "##\n..\n");
+    }
+
+    #[test]
+    fn grids() {
+        let grid = run("ab\r\ncd\n", char_grid).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+
+        let err = run("abc\nab\n", char_grid).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 3,
+                ..
+            }
+        ));
+
+        assert!(run("", char_grid).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::error::Result;
+use crate::input::Input;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsigned(n) => write!(f, "{n}"),
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Unsigned(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Signed(value)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Answer::Signed(value as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+
+// A day's puzzle: parse the input once, then answer both parts from the parsed form.
+pub trait Solution {
+    fn parse(input: &Input) -> Result<Self>
+    where
+        Self: Sized;
+
+    fn part1(&self) -> Result<Answer>;
+
+    fn part2(&self) -> Result<Answer>;
+}
+
+type ParseFn = fn(&Input) -> Result<Box<dyn Solution>>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Solver {
+    pub year: u16,
+    pub day: u8,
+    pub title: &'static str,
+    parse_fn: ParseFn,
+}
+
+impl Solver {
+    pub fn new<S: Solution + 'static>(year: u16, day: u8, title: &'static str) -> Self {
+        Solver {
+            year,
+            day,
+            title,
+            parse_fn: |input| Ok(Box::new(S::parse(input)?)),
+        }
+    }
+
+    pub fn parse(&self, input: &Input) -> Result<Parsed> {
+        let solution = (self.parse_fn)(input).map_err(|e| e.with_day(self.day))?;
+
+        Ok(Parsed {
+            day: self.day,
+            solution,
+        })
+    }
+
+    pub fn solve(&self, input: &Input) -> Result<(Answer, Answer)> {
+        let solution = self.parse(input)?;
+
+        Ok((solution.part1()?, solution.part2()?))
+    }
+}
+
+// A parsed solution whose errors are tagged with the day they came from.
+pub struct Parsed {
+    day: u8,
+    solution: Box<dyn Solution>,
+}
+
+impl Parsed {
+    pub fn part1(&self) -> Result<Answer> {
+        self.solution.part1().map_err(|e| e.with_day(self.day))
+    }
+
+    pub fn part2(&self) -> Result<Answer> {
+        self.solution.part2().map_err(|e| e.with_day(self.day))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    solvers: BTreeMap<(u16, u8), Solver>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    pub fn register<S: Solution + 'static>(
+        &mut self,
+        year: u16,
+        day: u8,
+        title: &'static str,
+    ) -> &mut Self {
+        self.solvers
+            .insert((year, day), Solver::new::<S>(year, day, title));
+        self
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Option<&Solver> {
+        self.solvers.get(&(year, day))
+    }
+
+    pub fn year(&self, year: u16) -> impl Iterator<Item = &Solver> {
+        self.solvers
+            .range((year, 0)..=(year, u8::MAX))
+            .map(|(_, s)| s)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Solver> {
+        self.solvers.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.solvers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solvers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AocError;
+
+    struct LineCount(Vec<String>);
+
+    impl Solution for LineCount {
+        fn parse(input: &Input) -> Result<Self> {
+            Ok(LineCount(input.lines().map(|l| l.to_owned()).collect()))
+        }
+
+        fn part1(&self) -> Result<Answer> {
+            Ok(self.0.len().into())
+        }
+
+        fn part2(&self) -> Result<Answer> {
+            Ok(self.0.last().map_or("", |l| l.as_str()).into())
+        }
+    }
+
+    #[test]
+    fn registry_lookup() {
+        let mut registry = Registry::new();
+        registry
+            .register::<LineCount>(2022, 25, "Lines")
+            .register::<LineCount>(2023, 2, "Lines")
+            .register::<LineCount>(2023, 1, "Lines");
+
+        assert_eq!(registry.len(), 3);
+        assert!(registry.get(2023, 3).is_none());
+
+        let days: Vec<u8> = registry.year(2023).map(|s| s.day).collect();
+        assert_eq!(days, vec![1, 2]);
+
+        let solver = registry.get(2022, 25).unwrap();
+        let (part1, part2) = solver.solve(&Input::new("a\nb\r\nc\n\n")).unwrap();
+
+        assert_eq!(part1, Answer::Unsigned(3));
+        assert_eq!(part2, Answer::Text("c".to_owned()));
+        assert_eq!(part1.to_string(), "3");
+    }
+
+    struct Broken;
+
+    impl Solution for Broken {
+        fn parse(input: &Input) -> Result<Self> {
+            match input.is_empty() {
+                true => Err(AocError::parse(1, 1, "no input")),
+                false => Ok(Broken),
+            }
+        }
+
+        fn part1(&self) -> Result<Answer> {
+            Err(AocError::overflow("part 1"))
+        }
+
+        fn part2(&self) -> Result<Answer> {
+            Err(AocError::invalid("part 2"))
+        }
+    }
+
+    #[test]
+    fn errors_carry_day() {
+        let solver = Solver::new::<Broken>(2023, 7, "Broken");
+
+        let err = solver.parse(&Input::new("")).err().unwrap();
+        assert_eq!(err.to_string(), "day 7: line 1, column 1: no input");
+
+        let parsed = solver.parse(&Input::new("x")).unwrap();
+        assert_eq!(parsed.part1().unwrap_err().day(), Some(7));
+        assert!(matches!(
+            parsed.part2(),
+            Err(AocError::InvalidState { day: Some(7), .. })
+        ));
+    }
+
+    #[test]
+    fn answer_display() {
+        assert_eq!(Answer::from(-12isize).to_string(), "-12");
+        assert_eq!(Answer::from(54985u32).to_string(), "54985");
+        assert_eq!(Answer::from("LR").to_string(), "LR");
+    }
+}
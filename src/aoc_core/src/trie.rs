@@ -0,0 +1,933 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::str::CharIndices;
+
+use crate::error::{AocError, Result};
+
+#[derive(Debug)]
+pub enum TrieOption<T> {
+    Value(Option<T>),
+    Empty,
+    None,
+}
+
+impl<T> TrieOption<T>
+where
+    T: Copy + Clone,
+{
+    pub fn unwrap(&self) -> Option<T> {
+        match *self {
+            TrieOption::Value(Some(val)) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TrieNode<T> {
+    pub value: Option<T>,
+    pub children: BTreeMap<char, TrieNode<T>>,
+}
+
+impl<T> Default for TrieNode<T>
+where
+    T: Clone,
+{
+    fn default() -> Self {
+        TrieNode {
+            value: None,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> TrieNode<T>
+where
+    T: Clone,
+{
+    pub fn add_str_recurse(&mut self, st: &[char], k: usize, value: T) -> Option<T> {
+        if k == st.len() {
+            return self.value.replace(value);
+        }
+
+        self.children
+            .entry(st[k])
+            .or_default()
+            .add_str_recurse(st, k + 1, value)
+    }
+
+    fn has_string_r(&self, st: &[char], k: usize) -> bool {
+        if k >= st.len() {
+            return true;
+        }
+
+        match self.children.get(&st[k]) {
+            Some(child) => child.has_string_r(st, k + 1),
+            None => false,
+        }
+    }
+
+    pub fn get_value_r(&self, st: &[char], k: usize) -> TrieOption<T> {
+        if k == st.len() {
+            if self.value.is_some() {
+                return TrieOption::Value(self.value.clone());
+            }
+            return TrieOption::Empty;
+        }
+
+        match self.children.get(&st[k]) {
+            Some(child) => child.get_value_r(st, k + 1),
+            None => TrieOption::None,
+        }
+    }
+
+    fn find(&self, st: &[char]) -> Option<&TrieNode<T>> {
+        st.iter().try_fold(self, |node, c| node.children.get(c))
+    }
+
+    // prunes the branches left without any values on the way back up
+    fn remove_r(&mut self, st: &[char], k: usize) -> Option<T> {
+        if k == st.len() {
+            return self.value.take();
+        }
+
+        let child = self.children.get_mut(&st[k])?;
+        let removed = child.remove_r(st, k + 1);
+
+        if child.value.is_none() && child.children.is_empty() {
+            self.children.remove(&st[k]);
+        }
+
+        removed
+    }
+}
+
+// characters that lowercase to more than one character (like 'İ') are
+// kept as they are so every key keeps its length
+fn fold_case(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(folded), None) => folded,
+        _ => c,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Trie<T> {
+    pub(crate) root: TrieNode<T>,
+    ignore_case: bool,
+    len: usize,
+    // how many keys there are of each length in characters
+    key_lengths: BTreeMap<usize, usize>,
+}
+
+impl<T> Default for Trie<T>
+where
+    T: Clone,
+{
+    fn default() -> Self {
+        Trie::new()
+    }
+}
+
+impl<T> Trie<T>
+where
+    T: Clone,
+{
+    pub fn new() -> Self {
+        Trie {
+            root: TrieNode::default(),
+            ignore_case: false,
+            len: 0,
+            key_lengths: BTreeMap::new(),
+        }
+    }
+
+    // keys and lookups both ignore case, so "One" finds "oNE"
+    pub fn case_insensitive() -> Self {
+        Trie {
+            root: TrieNode::default(),
+            ignore_case: true,
+            len: 0,
+            key_lengths: BTreeMap::new(),
+        }
+    }
+
+    pub fn ignores_case(&self) -> bool {
+        self.ignore_case
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn fold(&self, c: char) -> char {
+        if self.ignore_case {
+            fold_case(c)
+        } else {
+            c
+        }
+    }
+
+    fn key(&self, st: &str) -> Vec<char> {
+        st.chars().map(|c| self.fold(c)).collect()
+    }
+
+    // the length in characters of the longest key
+    fn longest(&self) -> usize {
+        self.key_lengths.keys().next_back().copied().unwrap_or(0)
+    }
+
+    pub fn has_string(&self, st: &str) -> bool {
+        self.root.has_string_r(&self.key(st), 0)
+    }
+
+    pub fn get_value(&self, st: &str) -> TrieOption<T> {
+        self.root.get_value_r(&self.key(st), 0)
+    }
+
+    pub fn get(&self, st: &str) -> Option<&T> {
+        self.root.find(&self.key(st))?.value.as_ref()
+    }
+
+    // an empty key would be a word found between every pair of characters,
+    // gives back the value the key had before, if any
+    pub fn add_string(&mut self, st: &str, value: T) -> Result<Option<T>> {
+        if st.is_empty() {
+            return Err(AocError::invalid("trie keys cannot be empty"));
+        }
+
+        let st = self.key(st);
+        let previous = self.root.add_str_recurse(&st, 0, value);
+        if previous.is_none() {
+            self.len += 1;
+            *self.key_lengths.entry(st.len()).or_default() += 1;
+        }
+
+        Ok(previous)
+    }
+
+    pub fn remove(&mut self, st: &str) -> Option<T> {
+        let key = self.key(st);
+        let removed = self.root.remove_r(&key, 0);
+        if removed.is_some() {
+            self.len -= 1;
+            if let Some(count) = self.key_lengths.get_mut(&key.len()) {
+                *count -= 1;
+                if *count == 0 {
+                    self.key_lengths.remove(&key.len());
+                }
+            }
+        }
+
+        removed
+    }
+
+    // the longest key that `st` starts with, as its length in bytes of `st`
+    // and its value
+    pub fn longest_prefix_match(&self, st: &str) -> Option<(usize, &T)> {
+        let mut node = &self.root;
+        let mut longest = None;
+
+        for (i, c) in st.char_indices() {
+            match node.children.get(&self.fold(c)) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = &node.value {
+                longest = Some((i + c.len_utf8(), value));
+            }
+        }
+
+        longest
+    }
+
+    // every key and value in lexical order, keys of a case insensitive
+    // trie come back lowercased
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            stack: vec![(String::new(), &self.root)],
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    // the keys starting with `prefix`, including `prefix` itself
+    pub fn iter_prefix(&self, prefix: &str) -> Iter<'_, T> {
+        let prefix = self.key(prefix);
+
+        Iter {
+            stack: self
+                .root
+                .find(&prefix)
+                .map(|node| vec![(prefix.into_iter().collect(), node)])
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn automaton(&self) -> AhoCorasick<T> {
+        AhoCorasick::new(self)
+    }
+
+    // every key within `max_distance` edits of `st`, closest first
+    pub fn fuzzy_get(&self, st: &str, max_distance: usize) -> Vec<FuzzyMatch<'_, T>> {
+        self.fuzzy(st, max_distance, false)
+    }
+
+    // every key and non-empty prefix of `st` within `max_distance` edits of
+    // each other, closest first and then longest prefix first
+    pub fn fuzzy_prefixes(&self, st: &str, max_distance: usize) -> Vec<FuzzyMatch<'_, T>> {
+        self.fuzzy(st, max_distance, true)
+    }
+
+    fn fuzzy(&self, st: &str, max_distance: usize, prefixes: bool) -> Vec<FuzzyMatch<'_, T>> {
+        // no key can match more characters than it has plus the insertions
+        let longest = self.longest();
+        let mut text = vec![];
+        let mut ends = vec![0];
+        for (i, c) in st.char_indices() {
+            if prefixes && text.len() == longest + max_distance {
+                break;
+            }
+            text.push(self.fold(c));
+            ends.push(i + c.len_utf8());
+        }
+
+        let search = FuzzySearch {
+            text,
+            ends,
+            max_distance,
+            prefixes,
+        };
+        let mut found = vec![];
+        let first_row = (0..=search.text.len()).collect();
+        search.walk(&self.root, &mut vec![], &mut vec![first_row], &mut found);
+
+        found.sort_by(|a, b| (a.distance, b.len, &a.key).cmp(&(b.distance, a.len, &b.key)));
+        found
+    }
+}
+
+// a key found by approximate matching, `len` is how many bytes of the
+// searched text it was matched against and `distance` how many edits apart
+// they are, a swap of two neighbouring characters counting as one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch<'t, T> {
+    pub key: String,
+    pub value: &'t T,
+    pub len: usize,
+    pub distance: usize,
+}
+
+struct FuzzySearch {
+    text: Vec<char>,
+    // byte offset after each number of characters of the text
+    ends: Vec<usize>,
+    max_distance: usize,
+    prefixes: bool,
+}
+
+impl FuzzySearch {
+    /*/ Walks the trie carrying one row of the edit distance table per key
+    character, rows[k][j] being the distance between the first k characters
+    of the key and the first j of the text. Sibling keys share the rows of
+    their common prefix and a branch stops once no cell is within reach.
+    */
+    fn walk<'t, T: Clone>(
+        &self,
+        node: &'t TrieNode<T>,
+        key: &mut Vec<char>,
+        rows: &mut Vec<Vec<usize>>,
+        found: &mut Vec<FuzzyMatch<'t, T>>,
+    ) {
+        let row = &rows[rows.len() - 1];
+        if row.iter().all(|&distance| distance > self.max_distance) {
+            return;
+        }
+
+        if let Some(value) = &node.value {
+            let lens = if self.prefixes {
+                1..row.len()
+            } else {
+                self.text.len()..row.len()
+            };
+
+            for len in lens.filter(|&len| row[len] <= self.max_distance) {
+                found.push(FuzzyMatch {
+                    key: key.iter().collect(),
+                    value,
+                    len: self.ends[len],
+                    distance: row[len],
+                });
+            }
+        }
+
+        for (&c, child) in &node.children {
+            let next = self.next_row(rows, key.last().copied(), c);
+            key.push(c);
+            rows.push(next);
+
+            self.walk(child, key, rows, found);
+
+            key.pop();
+            rows.pop();
+        }
+    }
+
+    fn next_row(&self, rows: &[Vec<usize>], last: Option<char>, c: char) -> Vec<usize> {
+        let above = &rows[rows.len() - 1];
+        let mut row = vec![above[0] + 1];
+
+        for j in 1..above.len() {
+            let substitute = above[j - 1] + usize::from(self.text[j - 1] != c);
+            let mut distance = substitute.min(above[j] + 1).min(row[j - 1] + 1);
+
+            // swapping the key's last two characters for the text's
+            if let (Some(last), Some(two_above)) = (last, rows.len().checked_sub(2)) {
+                if j > 1 && self.text[j - 1] == last && self.text[j - 2] == c {
+                    distance = distance.min(rows[two_above][j - 2] + 1);
+                }
+            }
+            row.push(distance);
+        }
+
+        row
+    }
+}
+
+pub struct Iter<'a, T> {
+    // nodes still to visit and the keys leading to them, smallest on top
+    stack: Vec<(String, &'a TrieNode<T>)>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((key, node)) = self.stack.pop() {
+            for (&c, child) in node.children.iter().rev() {
+                let mut child_key = key.clone();
+                child_key.push(c);
+                self.stack.push((child_key, child));
+            }
+
+            if let Some(value) = &node.value {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+// panics on an empty key, use `add_string` to handle those as errors
+impl<K, T> Extend<(K, T)> for Trie<T>
+where
+    K: AsRef<str>,
+    T: Clone,
+{
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.add_string(key.as_ref(), value)
+                .expect("trie keys cannot be empty");
+        }
+    }
+}
+
+impl<K, T> FromIterator<(K, T)> for Trie<T>
+where
+    K: AsRef<str>,
+    T: Clone,
+{
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        let mut trie = Trie::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+// a word found by the automaton, start and end are byte offsets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match<T> {
+    pub start: usize,
+    pub end: usize,
+    pub value: T,
+}
+
+#[derive(Debug, Clone)]
+struct State<T> {
+    next: BTreeMap<char, usize>,
+    fail: usize,
+    // length of the state's word in characters
+    depth: usize,
+    value: Option<T>,
+    // nearest state holding a value, starting with this one and then
+    // walking the failure links
+    output: Option<usize>,
+}
+
+/*/ The trie flattened into an Aho-Corasick automaton. Each state links to
+the longest proper suffix of its word that is also in the trie, so one pass
+over the text reports every word, including overlapping ones like the
+"one" and "eight" in "oneight".
+*/
+#[derive(Debug, Clone)]
+pub struct AhoCorasick<T> {
+    states: Vec<State<T>>,
+    ignore_case: bool,
+}
+
+const ROOT: usize = 0;
+
+// follows failure links until some suffix of the current word continues with `c`
+fn step<T>(states: &[State<T>], mut state: usize, c: char) -> usize {
+    loop {
+        if let Some(&next) = states[state].next.get(&c) {
+            return next;
+        }
+        if state == ROOT {
+            return ROOT;
+        }
+        state = states[state].fail;
+    }
+}
+
+impl<T> AhoCorasick<T>
+where
+    T: Clone,
+{
+    pub fn new(trie: &Trie<T>) -> Self {
+        let state = |depth, value| State {
+            next: BTreeMap::new(),
+            fail: ROOT,
+            depth,
+            value,
+            output: None,
+        };
+
+        // number the trie nodes breadth first so every state comes after
+        // the shorter suffixes its failure link can point at
+        let mut states = vec![state(0, None)];
+        let mut queue = VecDeque::from([&trie.root]);
+        let mut s = ROOT;
+
+        while let Some(node) = queue.pop_front() {
+            for (&c, child) in &node.children {
+                let next = states.len();
+                states[s].next.insert(c, next);
+                states.push(state(states[s].depth + 1, child.value.clone()));
+                queue.push_back(child);
+            }
+            s += 1;
+        }
+
+        for s in 0..states.len() {
+            let children: Vec<(char, usize)> = states[s]
+                .next
+                .iter()
+                .map(|(&c, &child)| (c, child))
+                .collect();
+
+            for (c, child) in children {
+                let fail = if s == ROOT {
+                    ROOT
+                } else {
+                    step(&states, states[s].fail, c)
+                };
+
+                states[child].fail = fail;
+                states[child].output = if states[child].value.is_some() {
+                    Some(child)
+                } else {
+                    states[fail].output
+                };
+            }
+        }
+
+        AhoCorasick {
+            states,
+            ignore_case: trie.ignore_case,
+        }
+    }
+
+    // every word in the haystack in order of where it ends, longest first
+    pub fn find_iter<'a, 'h>(&'a self, haystack: &'h str) -> FindIter<'a, 'h, T> {
+        FindIter {
+            automaton: self,
+            chars: haystack.char_indices(),
+            starts: vec![],
+            state: ROOT,
+            end: 0,
+            output: None,
+        }
+    }
+}
+
+pub struct FindIter<'a, 'h, T> {
+    automaton: &'a AhoCorasick<T>,
+    chars: CharIndices<'h>,
+    // byte offset of every character read so far, to turn the length of a
+    // word in characters back into where it started
+    starts: Vec<usize>,
+    state: usize,
+    end: usize,
+    // the next word ending at `end` still to report
+    output: Option<usize>,
+}
+
+impl<T> Iterator for FindIter<'_, '_, T>
+where
+    T: Clone,
+{
+    type Item = Match<T>;
+
+    fn next(&mut self) -> Option<Match<T>> {
+        let states = &self.automaton.states;
+
+        loop {
+            if let Some(found) = self.output {
+                let found = &states[found];
+                self.output = states[found.fail].output;
+
+                if let Some(value) = &found.value {
+                    return Some(Match {
+                        start: self.starts[self.starts.len() - found.depth],
+                        end: self.end,
+                        value: value.clone(),
+                    });
+                }
+                continue;
+            }
+
+            let (i, c) = self.chars.next()?;
+            let c = if self.automaton.ignore_case {
+                fold_case(c)
+            } else {
+                c
+            };
+
+            self.state = step(states, self.state, c);
+            self.starts.push(i);
+            self.end = self.chars.offset();
+            self.output = states[self.state].output;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trie_smoke() {
+        let mut t: Trie<usize> = Trie::new();
+        let st = "Andrew".to_lowercase();
+        let st2 = "Ashley".to_lowercase();
+
+        t.add_string(&st, 32).unwrap();
+        t.add_string(&st2, 1).unwrap();
+        let search_val = t.get_value(&st);
+        let search_val_not_found = t.get_value(&st2);
+
+        // println!("has_string: {}", t.has_string(&"Ash".to_lowercase()));
+
+        assert_eq!(32, search_val.unwrap().unwrap());
+        assert_eq!(1, search_val_not_found.unwrap().unwrap());
+
+        assert!(t.has_string(&"Ash".to_lowercase()));
+        assert!(!t.has_string(&"Ashleyyo".to_lowercase()));
+        assert!(!t.has_string("b"));
+        assert!(t.has_string(&"An".to_lowercase()));
+        assert!(!t.has_string(&"Andra".to_lowercase()));
+        assert!(!t.has_string("An7"));
+        assert!(matches!(t.get_value("A"), TrieOption::None));
+
+        // println!("t is: {:#?}, search_val:{:?}", &t, search_val);
+    }
+
+    fn found(automaton: &AhoCorasick<u32>, haystack: &str) -> Vec<(usize, u32)> {
+        automaton
+            .find_iter(haystack)
+            .map(|m| (m.start, m.value))
+            .collect()
+    }
+
+    #[test]
+    fn automaton_overlaps() {
+        let mut t: Trie<u32> = Trie::new();
+        for (word, value) in [
+            ("one", 1),
+            ("two", 2),
+            ("three", 3),
+            ("eight", 8),
+            ("nine", 9),
+        ] {
+            t.add_string(word, value).unwrap();
+        }
+        let automaton = t.automaton();
+
+        assert_eq!(found(&automaton, "eightwo"), [(0, 8), (4, 2)]);
+        assert_eq!(found(&automaton, "oneight"), [(0, 1), (2, 8)]);
+        assert_eq!(found(&automaton, "twone"), [(0, 2), (2, 1)]);
+        assert_eq!(found(&automaton, "eighthree"), [(0, 8), (4, 3)]);
+        assert_eq!(found(&automaton, "ninine"), [(2, 9)]);
+        assert_eq!(found(&automaton, "on3e two"), [(5, 2)]);
+        assert_eq!(found(&automaton, "ONE"), []);
+        assert_eq!(found(&automaton, ""), []);
+
+        let m = automaton.find_iter("xxnine").next().unwrap();
+        assert_eq!(
+            m,
+            Match {
+                start: 2,
+                end: 6,
+                value: 9
+            }
+        );
+    }
+
+    #[test]
+    fn automaton_nested_words() {
+        let mut t: Trie<usize> = Trie::new();
+        for (value, word) in ["he", "she", "his", "hers"].into_iter().enumerate() {
+            t.add_string(word, value).unwrap();
+        }
+        let automaton = t.automaton();
+
+        let words: Vec<_> = automaton
+            .find_iter("ushers")
+            .map(|m| (m.start, m.end, m.value))
+            .collect();
+
+        assert_eq!(words, [(1, 4, 1), (2, 4, 0), (2, 6, 3)]);
+    }
+
+    #[test]
+    fn unicode_keys() {
+        let mut t: Trie<usize> = Trie::new();
+        for (value, word) in ["Andrew", "año", "straße", "日本", "R2-D2", "a b"]
+            .into_iter()
+            .enumerate()
+        {
+            t.add_string(word, value).unwrap();
+        }
+
+        assert_eq!(t.get_value("Andrew").unwrap(), Some(0));
+        assert_eq!(t.get_value("año").unwrap(), Some(1));
+        assert_eq!(t.get_value("straße").unwrap(), Some(2));
+        assert_eq!(t.get_value("日本").unwrap(), Some(3));
+        assert_eq!(t.get_value("R2-D2").unwrap(), Some(4));
+        assert_eq!(t.get_value("a b").unwrap(), Some(5));
+
+        assert!(t.has_string("日"));
+        assert!(t.has_string("stra"));
+        assert!(!t.has_string("andrew"));
+        assert!(matches!(t.get_value("R2"), TrieOption::Empty));
+        assert!(matches!(t.get_value("ano"), TrieOption::None));
+
+        let words: Vec<_> = t
+            .automaton()
+            .find_iter("¿año日本?")
+            .map(|m| (m.start, m.end, m.value))
+            .collect();
+        assert_eq!(words, [(2, 6, 1), (6, 12, 3)]);
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let mut t: Trie<u32> = Trie::case_insensitive();
+        t.add_string("One", 1).unwrap();
+        t.add_string("ÉTÉ", 2).unwrap();
+
+        assert!(t.ignores_case());
+        assert_eq!(t.get_value("one").unwrap(), Some(1));
+        assert_eq!(t.get_value("oNE").unwrap(), Some(1));
+        assert_eq!(t.get_value("été").unwrap(), Some(2));
+        assert!(t.has_string("ON"));
+
+        let found: Vec<_> = t
+            .automaton()
+            .find_iter("xONEété")
+            .map(|m| m.value)
+            .collect();
+        assert_eq!(found, [1, 2]);
+
+        let mut t: Trie<u32> = Trie::new();
+        t.add_string("One", 1).unwrap();
+        assert!(!t.ignores_case());
+        assert!(matches!(t.get_value("one"), TrieOption::None));
+        assert_eq!(t.automaton().find_iter("one").count(), 0);
+    }
+
+    #[test]
+    fn empty_key() {
+        let mut t: Trie<u32> = Trie::new();
+
+        assert!(matches!(
+            t.add_string("", 1),
+            Err(AocError::InvalidState { .. })
+        ));
+        assert!(matches!(t.get_value(""), TrieOption::Empty));
+        assert_eq!(t.automaton().find_iter("abc").count(), 0);
+    }
+
+    #[test]
+    fn trie_api() {
+        let mut t: Trie<usize> = ["tea", "ten", "to", "inn", "in", "i", "ted"]
+            .into_iter()
+            .enumerate()
+            .map(|(value, key)| (key, value))
+            .collect();
+
+        assert_eq!(t.len(), 7);
+        assert!(!t.is_empty());
+        assert_eq!(t.get("ten"), Some(&1));
+        assert_eq!(t.get("te"), None);
+        assert_eq!(t.get("tenth"), None);
+
+        let keys: Vec<String> = t.keys().collect();
+        assert_eq!(keys, ["i", "in", "inn", "tea", "ted", "ten", "to"]);
+
+        let under_te: Vec<_> = t.iter_prefix("te").collect();
+        assert_eq!(
+            under_te,
+            [
+                ("tea".to_owned(), &0),
+                ("ted".to_owned(), &6),
+                ("ten".to_owned(), &1)
+            ]
+        );
+        assert_eq!(t.iter_prefix("in").count(), 2);
+        assert_eq!(t.iter_prefix("x").count(), 0);
+        assert_eq!(t.iter_prefix("").count(), 7);
+
+        assert_eq!(t.longest_prefix_match("inner"), Some((3, &3)));
+        assert_eq!(t.longest_prefix_match("ink"), Some((2, &4)));
+        assert_eq!(t.longest_prefix_match("te"), None);
+        assert_eq!(t.longest_prefix_match(""), None);
+
+        assert_eq!(t.add_string("ten", 10).unwrap(), Some(1));
+        assert_eq!(t.len(), 7);
+
+        assert_eq!(t.remove("in"), Some(4));
+        assert_eq!(t.remove("in"), None);
+        assert_eq!(t.remove("te"), None);
+        assert_eq!(t.get("inn"), Some(&3));
+        assert_eq!(t.remove("inn"), Some(3));
+        assert!(!t.has_string("in"));
+        assert!(t.has_string("i"));
+        assert_eq!(t.len(), 5);
+
+        t.extend([("tea".to_owned(), 20), ("zed".to_owned(), 21)]);
+        assert_eq!(t.len(), 6);
+        assert_eq!(t.get("tea"), Some(&20));
+
+        for key in ["i", "tea", "ted", "ten", "to", "zed"] {
+            t.remove(key);
+        }
+        assert!(t.is_empty());
+        assert_eq!(t.iter().count(), 0);
+        assert!(!t.has_string("t"));
+    }
+
+    #[test]
+    fn trie_api_ignoring_case() {
+        let mut t: Trie<String> = Trie::case_insensitive();
+        t.extend([("Año", "year".to_owned()), ("AÑOS", "years".to_owned())]);
+
+        assert_eq!(t.get("AÑO").map(String::as_str), Some("year"));
+        assert_eq!(t.keys().collect::<Vec<_>>(), ["año", "años"]);
+        assert_eq!(t.iter_prefix("AÑ").count(), 2);
+        assert_eq!(
+            t.longest_prefix_match("AñOs!"),
+            Some((5, &"years".to_owned()))
+        );
+        assert_eq!(t.remove("años"), Some("years".to_owned()));
+        assert_eq!(t.len(), 1);
+    }
+
+    #[test]
+    fn fuzzy_lookups() {
+        let t: Trie<u32> = [
+            ("one", 1),
+            ("two", 2),
+            ("seven", 7),
+            ("nine", 9),
+            ("nineteen", 19),
+        ]
+        .into_iter()
+        .collect();
+
+        let found = |st, max| -> Vec<(String, u32, usize, usize)> {
+            t.fuzzy_get(st, max)
+                .into_iter()
+                .map(|m| (m.key, *m.value, m.len, m.distance))
+                .collect()
+        };
+
+        assert_eq!(found("nine", 0), [("nine".to_owned(), 9, 4, 0)]);
+        assert_eq!(found("nien", 1), [("nine".to_owned(), 9, 4, 1)]);
+        assert_eq!(found("sevn", 1), [("seven".to_owned(), 7, 4, 1)]);
+        assert_eq!(found("sevne", 1), [("seven".to_owned(), 7, 5, 1)]);
+        assert_eq!(found("too", 1), [("two".to_owned(), 2, 3, 1)]);
+        assert!(found("nien", 0).is_empty());
+        assert!(found("xyz", 2).is_empty());
+        assert_eq!(found("on", 2), [("one".to_owned(), 1, 2, 1)]);
+        assert_eq!(found("on", 3).len(), 3);
+
+        let prefixes = |st, max| -> Vec<(String, usize, usize)> {
+            t.fuzzy_prefixes(st, max)
+                .into_iter()
+                .map(|m| (m.key, m.len, m.distance))
+                .collect()
+        };
+
+        assert_eq!(prefixes("sevn9x", 1), [("seven".to_owned(), 4, 1)]);
+        assert_eq!(
+            prefixes("ninetene!", 1),
+            [
+                ("nine".to_owned(), 4, 0),
+                ("nineteen".to_owned(), 8, 1),
+                ("nineteen".to_owned(), 7, 1),
+                ("nine".to_owned(), 5, 1),
+                ("nine".to_owned(), 3, 1)
+            ]
+        );
+        assert_eq!(
+            prefixes("nien", 1),
+            [("nine".to_owned(), 4, 1), ("nine".to_owned(), 3, 1)]
+        );
+        assert!(prefixes("", 1).is_empty());
+
+        let mut t: Trie<u32> = Trie::case_insensitive();
+        t.add_string("Año", 1).unwrap();
+        let found = t.fuzzy_prefixes("AOÑx", 1);
+        assert_eq!((found[0].len, found[0].distance), (4, 1));
+    }
+
+    #[test]
+    fn longest_key_tracking() {
+        let mut t: Trie<u32> = Trie::new();
+        assert_eq!(t.longest(), 0);
+
+        t.extend([("nine", 9), ("nineteen", 19), ("eighteen", 18)]);
+        assert_eq!(t.longest(), 8);
+
+        t.add_string("nineteen", 91).unwrap();
+        t.remove("nineteen");
+        assert_eq!(t.longest(), 8);
+        t.remove("eighteen");
+        assert_eq!(t.longest(), 4);
+        assert_eq!(t.remove("eighteen"), None);
+        assert_eq!(t.longest(), 4);
+
+        let mut t: Trie<u32> = Trie::case_insensitive();
+        t.add_string("Año", 1).unwrap();
+        assert_eq!(t.longest(), 3);
+    }
+}
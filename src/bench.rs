@@ -0,0 +1,340 @@
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use aoc_core::input::Input;
+use aoc_core::solution::Solver;
+use serde::{Deserialize, Serialize};
+
+use crate::runner::{self, InputSource};
+
+pub const PHASES: [&str; 3] = ["parse", "part1", "part2"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    // nearest-rank percentiles over the collected samples
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let rank = |percentile: usize| {
+            let idx = (percentile * sorted.len()).div_ceil(100);
+            sorted[idx.saturating_sub(1)]
+        };
+
+        Some(Stats {
+            min: sorted[0],
+            median: rank(50),
+            p95: rank(95),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub timestamp: u64,
+    pub commit: Option<String>,
+    pub year: u16,
+    pub day: u8,
+    pub phase: String,
+    pub iterations: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64,
+}
+
+impl BenchRecord {
+    fn median(&self) -> Duration {
+        Duration::from_nanos(self.median_ns)
+    }
+}
+
+pub struct BenchOptions {
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    pub iterations: usize,
+    pub warmup: usize,
+    pub threshold: f64,
+    pub history: PathBuf,
+}
+
+pub fn default_history_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("bench_history.jsonl")
+}
+
+// returns the parse, part1 and part2 samples, in that order
+pub fn sample_solver(
+    solver: &Solver,
+    input: &Input,
+    iterations: usize,
+    warmup: usize,
+) -> Result<[Vec<Duration>; 3]> {
+    let mut samples: [Vec<Duration>; 3] = Default::default();
+
+    for iteration in 0..warmup + iterations {
+        let start = Instant::now();
+        let solution = solver.parse(input)?;
+        let parse_elapsed = start.elapsed();
+
+        let start = Instant::now();
+        solution.part1()?;
+        let part1_elapsed = start.elapsed();
+
+        let start = Instant::now();
+        solution.part2()?;
+        let part2_elapsed = start.elapsed();
+
+        if iteration >= warmup {
+            samples[0].push(parse_elapsed);
+            samples[1].push(part1_elapsed);
+            samples[2].push(part2_elapsed);
+        }
+    }
+
+    Ok(samples)
+}
+
+pub fn load_history(path: &Path) -> Result<Vec<BenchRecord>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Could not open bench history {}", path.display()))?;
+
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.as_ref().is_ok_and(|l| l.trim().is_empty()))
+        .map(|(ix, line)| {
+            let line = line?;
+            serde_json::from_str(&line)
+                .with_context(|| format!("Invalid bench record at {}:{}", path.display(), ix + 1))
+        })
+        .collect()
+}
+
+pub fn append_history(path: &Path, records: &[BenchRecord]) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Could not open bench history {}", path.display()))?;
+
+    for record in records {
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+    }
+
+    Ok(())
+}
+
+pub fn previous_record<'a>(
+    history: &'a [BenchRecord],
+    year: u16,
+    day: u8,
+    phase: &str,
+) -> Option<&'a BenchRecord> {
+    history
+        .iter()
+        .rev()
+        .find(|r| r.year == year && r.day == day && r.phase == phase)
+}
+
+// relative change of the median against the previous run, as a percentage
+pub fn median_change(previous: &BenchRecord, stats: &Stats) -> f64 {
+    let previous = previous.median().as_secs_f64();
+    if previous == 0.0 {
+        return 0.0;
+    }
+
+    (stats.median.as_secs_f64() - previous) / previous * 100.0
+}
+
+fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+pub fn bench(options: BenchOptions) -> Result<()> {
+    if options.iterations == 0 {
+        bail!("Need at least one iteration to benchmark");
+    }
+
+    let registry = runner::registry();
+    let history = load_history(&options.history)?;
+    let commit = current_commit();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    let solvers: Vec<&Solver> = match options.year {
+        Some(year) => registry.year(year).collect(),
+        None => registry.iter().collect(),
+    };
+
+    println!(
+        "{:<4} {:>3}  {:<5} {:>12} {:>12} {:>12} {:>12}",
+        "year", "day", "phase", "min", "median", "p95", "prev median"
+    );
+
+    let mut records = vec![];
+    let mut slowdowns = 0usize;
+
+    for solver in solvers
+        .into_iter()
+        .filter(|s| options.day.is_none_or(|day| s.day == day))
+    {
+        let input = runner::read_input(solver, &InputSource::Fixture)?;
+        let samples = sample_solver(solver, &input, options.iterations, options.warmup)
+            .with_context(|| format!("{} day {} failed", solver.year, solver.day))?;
+
+        for (phase, samples) in PHASES.iter().zip(samples.iter()) {
+            let Some(stats) = Stats::from_samples(samples) else {
+                continue;
+            };
+
+            let previous = previous_record(&history, solver.year, solver.day, phase);
+            let comparison = previous.map_or(String::new(), |prev| {
+                let change = median_change(prev, &stats);
+                let flag = if change > options.threshold {
+                    slowdowns += 1;
+                    "  SLOWER"
+                } else {
+                    ""
+                };
+                format!(
+                    "{:>12} {change:+7.1}%{flag}",
+                    format!("{:?}", prev.median())
+                )
+            });
+
+            println!(
+                "{:<4} {:>3}  {:<5} {:>12} {:>12} {:>12} {}",
+                solver.year,
+                solver.day,
+                phase,
+                format!("{:?}", stats.min),
+                format!("{:?}", stats.median),
+                format!("{:?}", stats.p95),
+                comparison
+            );
+
+            records.push(BenchRecord {
+                timestamp,
+                commit: commit.clone(),
+                year: solver.year,
+                day: solver.day,
+                phase: phase.to_string(),
+                iterations: samples.len(),
+                min_ns: stats.min.as_nanos() as u64,
+                median_ns: stats.median.as_nanos() as u64,
+                p95_ns: stats.p95.as_nanos() as u64,
+            });
+        }
+    }
+
+    append_history(&options.history, &records)?;
+
+    if slowdowns > 0 {
+        println!(
+            "{slowdowns} phase(s) slower than the previous run by more than {}%",
+            options.threshold
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn stats_from_samples() {
+        let samples: Vec<Duration> = (1..=20).rev().map(ms).collect();
+        let stats = Stats::from_samples(&samples).unwrap();
+
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, ms(10));
+        assert_eq!(stats.p95, ms(19));
+
+        let single = Stats::from_samples(&[ms(7)]).unwrap();
+        assert_eq!(
+            (single.min, single.median, single.p95),
+            (ms(7), ms(7), ms(7))
+        );
+
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn history_roundtrip() {
+        let path = std::env::temp_dir().join(format!("bench_history_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let record = |day, median_ns| BenchRecord {
+            timestamp: 0,
+            commit: Some("abc1234".to_owned()),
+            year: 2023,
+            day,
+            phase: "part1".to_owned(),
+            iterations: 10,
+            min_ns: median_ns,
+            median_ns,
+            p95_ns: median_ns,
+        };
+
+        append_history(&path, &[record(1, 100), record(2, 100)]).unwrap();
+        append_history(&path, &[record(1, 200)]).unwrap();
+
+        let history = load_history(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(history.len(), 3);
+
+        let previous = previous_record(&history, 2023, 1, "part1").unwrap();
+        assert_eq!(previous.median_ns, 200);
+        assert!(previous_record(&history, 2023, 1, "parse").is_none());
+
+        let stats = Stats {
+            min: Duration::from_nanos(250),
+            median: Duration::from_nanos(250),
+            p95: Duration::from_nanos(250),
+        };
+        assert_eq!(median_change(previous, &stats), 25.0);
+    }
+
+    #[test]
+    fn sample_solver_phases() {
+        let registry = runner::registry();
+        let solver = registry.get(2023, 9).unwrap();
+
+        let samples = sample_solver(solver, &Input::new("0 3 6 9 12 15"), 5, 2).unwrap();
+        assert!(samples.iter().all(|s| s.len() == 5));
+    }
+}
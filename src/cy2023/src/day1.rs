@@ -1,148 +1,677 @@
-use std::str::FromStr;
+use std::cmp::Reverse;
+use std::fmt::Write;
+use std::io::{BufRead, ErrorKind, Read};
 
-use anyhow::Result;
+use serde::Serialize;
 
-mod trie;
-use trie::{Trie, TrieOption};
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::solution::{Answer, Solution};
+use aoc_core::trie::{AhoCorasick, Trie};
 
-pub fn process_line(line_numbers: &Vec<u32>) -> u32 {
-    let num1: u32;
-    let num2: u32;
+const ENGLISH: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
 
-    if line_numbers.len() < 2 {
-        num1 = line_numbers[0];
-        num2 = num1;
-    } else {
-        num1 = line_numbers[0];
-        num2 = line_numbers[line_numbers.len() - 1];
+const SPANISH: [(&str, u32); 9] = [
+    ("uno", 1),
+    ("dos", 2),
+    ("tres", 3),
+    ("cuatro", 4),
+    ("cinco", 5),
+    ("seis", 6),
+    ("siete", 7),
+    ("ocho", 8),
+    ("nueve", 9),
+];
+
+const GERMAN: [(&str, u32); 9] = [
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+];
+
+const FRENCH: [(&str, u32); 9] = [
+    ("un", 1),
+    ("deux", 2),
+    ("trois", 3),
+    ("quatre", 4),
+    ("cinq", 5),
+    ("six", 6),
+    ("sept", 7),
+    ("huit", 8),
+    ("neuf", 9),
+];
+
+const HEXADECIMAL: [(&str, u32); 16] = [
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("ten", 10),
+    ("eleven", 11),
+    ("twelve", 12),
+    ("thirteen", 13),
+    ("fourteen", 14),
+    ("fifteen", 15),
+];
+
+// the digits of a number in `radix`, most significant first
+fn digits(value: u32, radix: u32) -> impl Iterator<Item = u32> {
+    let places = value.checked_ilog(radix).unwrap_or(0);
+    (0..=places)
+        .rev()
+        .map(move |place| value / radix.pow(place) % radix)
+}
+
+/*/ How the digits of a line become its calibration value: the first `take`
+and the last `take` digits read together as one number in `radix`. A line
+with fewer digits than that uses what it has from each end, so the puzzle's
+rule of one digit from each end in base 10 reads "7" as 77.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Rule {
+    take: usize,
+    radix: u32,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule { take: 1, radix: 10 }
+    }
+}
+
+impl Rule {
+    pub fn new(take: usize, radix: u32) -> Result<Self> {
+        if !(2..=36).contains(&radix) {
+            return Err(AocError::invalid(format!(
+                "calibration radix {radix} is not between 2 and 36"
+            )));
+        }
+
+        // every value has to fit in a u128, even one taking all its digits
+        let fits = take
+            .checked_mul(2)
+            .and_then(|width| u32::try_from(width).ok())
+            .and_then(|width| u128::from(radix).checked_pow(width))
+            .is_some();
+        if take == 0 || !fits {
+            return Err(AocError::invalid(format!(
+                "cannot take {take} digits from each end of a line in base {radix}"
+            )));
+        }
+
+        Ok(Rule { take, radix })
+    }
+
+    pub fn take(&self) -> usize {
+        self.take
+    }
+
+    pub fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    fn ends<'d>(&self, digits: &'d [u32]) -> (&'d [u32], &'d [u32]) {
+        let take = self.take.min(digits.len());
+        (&digits[..take], &digits[digits.len() - take..])
     }
 
-    let interim = (num1 << 3) + num1 + num1 + num2;
+    // nothing when the line has no digits at all
+    pub fn value(&self, digits: &[u32]) -> Option<u128> {
+        if digits.is_empty() {
+            return None;
+        }
+
+        let (first, last) = self.ends(digits);
+        let value = first.iter().chain(last).fold(0, |value, &digit| {
+            value * u128::from(self.radix) + u128::from(digit)
+        });
+
+        Some(value)
+    }
+}
 
-    // print!("=> {:?}", &interim);
-    interim
+/*/ Number words and the values they stand for. A word worth more than a
+single digit counts as all of its digits, so in base 10 "twelve" reads as a
+1 followed by a 2 and "twenty" as a 2 followed by a 0.
+*/
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    words: Trie<u32>,
+    automaton: AhoCorasick<u32>,
+}
+
+impl Dictionary {
+    pub fn new<K: AsRef<str>>(words: impl IntoIterator<Item = (K, u32)>) -> Result<Self> {
+        Dictionary::from_trie(Trie::new()).with_words(words)
+    }
+
+    pub fn from_trie(words: Trie<u32>) -> Self {
+        Dictionary {
+            automaton: words.automaton(),
+            words,
+        }
+    }
+
+    pub fn english() -> Self {
+        Dictionary::from_trie(ENGLISH.into_iter().collect())
+    }
+
+    pub fn spanish() -> Self {
+        Dictionary::from_trie(SPANISH.into_iter().collect())
+    }
+
+    pub fn german() -> Self {
+        Dictionary::from_trie(GERMAN.into_iter().collect())
+    }
+
+    pub fn french() -> Self {
+        Dictionary::from_trie(FRENCH.into_iter().collect())
+    }
+
+    // the english words for every hexadecimal digit, zero to fifteen
+    pub fn hexadecimal() -> Self {
+        Dictionary::from_trie(HEXADECIMAL.into_iter().collect())
+    }
+
+    // the english words for every octal digit, zero to seven
+    pub fn octal() -> Self {
+        Dictionary::from_trie(HEXADECIMAL[..8].iter().copied().collect())
+    }
+
+    // adds to the dictionary, replacing the values of words already in it
+    pub fn with_words<K: AsRef<str>>(
+        mut self,
+        words: impl IntoIterator<Item = (K, u32)>,
+    ) -> Result<Self> {
+        for (word, value) in words {
+            self.words.add_string(word.as_ref(), value)?;
+        }
+
+        Ok(Dictionary::from_trie(self.words))
+    }
+
+    pub fn words(&self) -> &Trie<u32> {
+        &self.words
+    }
+
+    pub fn tokens(&self, line: &str) -> Vec<Token> {
+        self.tokens_in(line, 10)
+    }
+
+    // digits of `radix` and number words in the order they appear,
+    // overlapping words like the "two" and "one" in "twone" both count.
+    // Letters inside a word are not digits too, so in base 16 "fifteen"
+    // is only 15 and not also an f and two e's
+    pub fn tokens_in(&self, line: &str, radix: u32) -> Vec<Token> {
+        let token = |start, end, value| Token {
+            start,
+            end,
+            text: line[start..end].to_owned(),
+            value,
+            distance: 0,
+        };
+
+        let words: Vec<Token> = self
+            .automaton
+            .find_iter(line)
+            .map(|m| token(m.start, m.end, m.value))
+            .collect();
+        let in_word = covered_bytes(line.len(), &words);
+
+        let mut found: Vec<Token> = line
+            .char_indices()
+            .filter(|&(i, _)| !in_word[i])
+            .filter_map(|(i, ch)| Some(token(i, i + ch.len_utf8(), ch.to_digit(radix)?)))
+            .collect();
+        found.extend(words);
+        found.sort_by_key(|t| (t.start, t.end));
+
+        found
+    }
+
+    /*/ The exact tokens plus the closest misspelt words that overlap neither
+    them nor each other. A word needs at least four letters for every edit
+    to be read as misspelt, so "nien" is a nine but "on" is not a one.
+    */
+    pub fn fuzzy_tokens_in(&self, line: &str, radix: u32, max_distance: usize) -> Vec<Token> {
+        let mut tokens = self.tokens_in(line, radix);
+        if max_distance == 0 {
+            return tokens;
+        }
+
+        let mut candidates: Vec<Token> = line
+            .char_indices()
+            .flat_map(|(start, _)| {
+                self.words
+                    .fuzzy_prefixes(&line[start..], max_distance)
+                    .into_iter()
+                    .filter(|m| m.distance > 0 && m.distance * 4 <= m.key.chars().count())
+                    .map(move |m| Token {
+                        start,
+                        end: start + m.len,
+                        text: line[start..start + m.len].to_owned(),
+                        value: *m.value,
+                        distance: m.distance,
+                    })
+            })
+            .collect();
+        candidates.sort_by_key(|t| (t.distance, t.start, Reverse(t.end)));
+
+        let mut taken = covered_bytes(line.len(), &tokens);
+        let mut accepted: Vec<Token> = vec![];
+        for candidate in candidates {
+            let span = &mut taken[candidate.start..candidate.end];
+            if !span.contains(&true) {
+                span.fill(true);
+                accepted.push(candidate);
+            }
+        }
+
+        tokens.extend(accepted);
+        tokens.sort_by_key(|t| (t.start, t.end));
+        tokens
+    }
+
+    pub fn digits(&self, line: &str) -> Vec<u32> {
+        self.tokens(line)
+            .iter()
+            .flat_map(|t| digits(t.value, 10))
+            .collect()
+    }
+}
+
+// which bytes of a line of `len` bytes are inside one of `tokens`
+fn covered_bytes(len: usize, tokens: &[Token]) -> Vec<bool> {
+    let mut covered = vec![false; len];
+    for token in tokens {
+        covered[token.start..token.end].fill(true);
+    }
+
+    covered
+}
+
+// a digit or number word found on a line, start and end are byte offsets
+// and `distance` is how many edits a misspelt word is from the real one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub value: u32,
+    pub distance: usize,
+}
+
+// how one line added up, `value` is missing when the line has no digits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineReport {
+    pub line: usize,
+    pub tokens: Vec<Token>,
+    pub first: Vec<u32>,
+    pub last: Vec<u32>,
+    pub value: Option<u128>,
+}
+
+impl LineReport {
+    fn new(line: usize, tokens: Vec<Token>, rule: Rule) -> Self {
+        let digits: Vec<u32> = tokens
+            .iter()
+            .flat_map(|t| digits(t.value, rule.radix))
+            .collect();
+        let (first, last) = rule.ends(&digits);
+
+        LineReport {
+            line,
+            first: first.to_vec(),
+            last: last.to_vec(),
+            value: rule.value(&digits),
+            tokens,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CalibrationReport {
+    pub rule: Rule,
+    pub lines: Vec<LineReport>,
+}
+
+impl CalibrationReport {
+    pub fn new(text: &str, dictionary: &Dictionary) -> Self {
+        CalibrationReport::with_rule(text, dictionary, Rule::default())
+    }
+
+    pub fn with_rule(text: &str, dictionary: &Dictionary, rule: Rule) -> Self {
+        CalibrationReport::from_tokens(text, rule, |line| dictionary.tokens_in(line, rule.radix))
+    }
+
+    // also reads misspelt words up to `max_distance` edits away
+    pub fn fuzzy(text: &str, dictionary: &Dictionary, rule: Rule, max_distance: usize) -> Self {
+        CalibrationReport::from_tokens(text, rule, |line| {
+            dictionary.fuzzy_tokens_in(line, rule.radix, max_distance)
+        })
+    }
+
+    fn from_tokens(text: &str, rule: Rule, tokens: impl Fn(&str) -> Vec<Token>) -> Self {
+        CalibrationReport {
+            rule,
+            lines: text
+                .split_terminator('\n')
+                .enumerate()
+                .map(|(i, line)| LineReport::new(i + 1, tokens(line), rule))
+                .collect(),
+        }
+    }
+
+    // every misspelt word that was accepted, with the line it is on
+    pub fn fuzzy_matches(&self) -> impl Iterator<Item = (usize, &Token)> {
+        self.lines.iter().flat_map(|line| {
+            line.tokens
+                .iter()
+                .filter(|t| t.distance > 0)
+                .map(move |t| (line.line, t))
+        })
+    }
+
+    // added up as a u128, then checked to fit in `T`
+    pub fn total<T: TryFrom<u128>>(&self) -> Result<T> {
+        let total = self.lines.iter().try_fold(0u128, |total, line| {
+            let value = line
+                .value
+                .ok_or_else(|| AocError::parse(line.line, 1, "Calibration line has no digits"))?;
+
+            total
+                .checked_add(value)
+                .ok_or_else(|| AocError::overflow("calibration total"))
+        })?;
+
+        T::try_from(total).map_err(|_| AocError::overflow("calibration total"))
+    }
+
+    // one row per line with its tokens as text@offset, misspelt words as
+    // text~distance@offset, then the total
+    pub fn table(&self) -> String {
+        let or_dash = |value: Option<u128>| value.map_or("-".to_owned(), |v| v.to_string());
+        let radix_digits = |digits: &[u32]| -> String {
+            if digits.is_empty() {
+                return "-".to_owned();
+            }
+            digits
+                .iter()
+                .filter_map(|&d| char::from_digit(d, self.rule.radix))
+                .collect()
+        };
+        let mut table = format!(
+            "{:>5} {:>6} {:>5} {:>5}  tokens\n",
+            "line", "value", "first", "last"
+        );
+
+        for line in &self.lines {
+            let tokens: Vec<String> = line
+                .tokens
+                .iter()
+                .map(|t| match t.distance {
+                    0 => format!("{}@{}", t.text, t.start),
+                    distance => format!("{}~{distance}@{}", t.text, t.start),
+                })
+                .collect();
+
+            let _ = writeln!(
+                table,
+                "{:>5} {:>6} {:>5} {:>5}  {}",
+                line.line,
+                or_dash(line.value),
+                radix_digits(&line.first),
+                radix_digits(&line.last),
+                tokens.join(" ")
+            );
+        }
+
+        let _ = match self.total::<u128>() {
+            Ok(total) => writeln!(table, "total {total}"),
+            Err(e) => writeln!(table, "total: {e}"),
+        };
+
+        table
+    }
+
+    pub fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+// first digit and last digit of the line read as a two digit number
+pub fn process_line(line_numbers: &[u32]) -> Option<u32> {
+    let num1 = *line_numbers.first()?;
+    let num2 = *line_numbers.last()?;
+
+    Some(num1 * 10 + num2)
+}
+
+fn add_line(total: u32, line: usize, line_numbers: &[u32]) -> Result<u32> {
+    let line_total = process_line(line_numbers)
+        .ok_or_else(|| AocError::parse(line, 1, "Calibration line has no digits"))?;
+
+    total
+        .checked_add(line_total)
+        .ok_or_else(|| AocError::overflow("calibration total"))
 }
 
 pub fn process_calibration(file_contents: String) -> Result<u32> {
     let mut running_numbers: Vec<u32> = vec![];
     let mut total = 0;
+    let mut line = 1;
 
     for ch in file_contents.chars() {
         if ch == '\n' {
-            let line_total = process_line(&running_numbers);
-            total += line_total;
+            total = add_line(total, line, &running_numbers)?;
             running_numbers.clear();
-        } else {
-            if ch.is_numeric() {
-                running_numbers.push(ch.to_digit(10).expect("Should be a number"));
-                // println!("{:?}", &running_numbers);
-            }
+            line += 1;
+        } else if let Some(digit) = ch.to_digit(10) {
+            running_numbers.push(digit);
         }
     }
 
-    if running_numbers.len() > 0 {
-        let line_total = process_line(&running_numbers);
-        total += line_total;
+    // the last line need not end in a newline
+    if !file_contents.is_empty() && !file_contents.ends_with('\n') {
+        total = add_line(total, line, &running_numbers)?;
     }
 
     Ok(total)
 }
 
-pub fn process_calibration_part2(file_contents: String, trie: &'static Trie<u32>) -> Result<u32> {
-    let mut running_numbers: Vec<u32> = vec![];
-    let mut running_number_word = String::new();
-    let mut total = 0;
-
-    // let mut line = 0;
-    // let mut lines = file_contents.lines();
+// what the streaming readers call their input in errors
+const STREAM: &str = "<calibration stream>";
 
-    for ch in file_contents.chars() {
-        // print!("Letter->{}", ch);
-        if ch == '\n' {
-            // line += 1;
-            // print!("Line:{}, nums: {:?} ", line, &running_numbers,);
-            let line_total = process_line(&running_numbers);
-            // println!(" => {}", lines.next().unwrap());
-            total += line_total;
-            running_numbers.clear();
-            running_number_word.clear();
-        } else {
-            if ch.is_numeric() {
-                running_numbers.push(ch.to_digit(10).expect("Should be a number"));
-                running_number_word.clear();
-                // println!("Found digit: {:?}", &running_numbers);
-                continue;
-            }
+/*/ Part 1 over any reader, in fixed size chunks so memory stays bounded
+however long the input or its lines are. Only the first and last digit of
+the current line are kept. Bytes of a multi-byte UTF-8 character are never
+ASCII, so scanning bytes finds the same digits as scanning chars.
+*/
+pub fn process_calibration_reader(mut reader: impl Read) -> Result<u32> {
+    let add_ends = |total, line, ends: Option<(u32, u32)>| match ends {
+        Some((first, last)) => add_line(total, line, &[first, last]),
+        None => add_line(total, line, &[]),
+    };
+    let mut chunk = [0u8; 8 * 1024];
+    let mut ends: Option<(u32, u32)> = None;
+    let mut total = 0;
+    let mut line = 1;
+    let mut line_open = false;
 
-            let candidate_str = &format!("{running_number_word}{ch}");
-            // println!("Checking number word:{}", candidate_str);
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(AocError::io(STREAM, e)),
+        };
 
-            match trie.get_value(candidate_str) {
-                TrieOption::Value(Some(number)) => {
-                    // println!("Found number: {}", &number);
-                    running_numbers.push(number);
-                    running_number_word.clear();
-                    running_number_word.push(ch); // keep last matching char in case it matches
-                }
-                TrieOption::Empty | TrieOption::Value(None) => {
-                    running_number_word.push(ch);
-                    // println!(
-                    //     "Found prefix, Running number word now is: {}",
-                    //     &running_number_word
-                    // );
+        for &byte in &chunk[..read] {
+            match byte {
+                b'\n' => {
+                    total = add_ends(total, line, ends.take())?;
+                    line += 1;
+                    line_open = false;
                 }
-                TrieOption::None => {
-                    if candidate_str.len() > 1 {
-                        running_number_word = String::from_str(&running_number_word[1..]).unwrap();
-                        // running_number_word.clear();
-                        running_number_word.push(ch);
-                        // println!("Clearing Running number word, but keeping: {}", ch);
-                    } else {
-                        // println!("Clearing Running number word");
-                        running_number_word.clear();
-                    }
+                b'0'..=b'9' => {
+                    let digit = u32::from(byte - b'0');
+                    ends = Some(ends.map_or((digit, digit), |(first, _)| (first, digit)));
+                    line_open = true;
                 }
+                _ => line_open = true,
             }
         }
     }
 
-    // println!("Final running_number_word:{}", &running_number_word);
-    // if running_number_word.len() > 0 {
-    //     if let Some(number) = trie.get_value(&running_number_word) {
-    //         running_numbers.push(number);
-    //     }
-    // }
-
-    // println!("Final running_numbers:{:?}", &running_numbers);
-    if running_numbers.len() > 0 {
-        let line_total = process_line(&running_numbers);
-        total += line_total;
+    // the last line need not end in a newline
+    if line_open {
+        total = add_ends(total, line, ends)?;
     }
 
     Ok(total)
 }
 
+// the longest line `calibrate_reader` will hold, in bytes before its newline
+pub const MAX_LINE_LEN: usize = 64 * 1024;
+
+// `calibrate_with` over any buffered reader, holding one line at a time,
+// lines longer than `MAX_LINE_LEN` are an error rather than read whole
+pub fn calibrate_reader<T: TryFrom<u128>>(
+    mut reader: impl BufRead,
+    dictionary: &Dictionary,
+    rule: Rule,
+) -> Result<T> {
+    let mut buffer = Vec::new();
+    let mut total = 0u128;
+    let mut line = 0;
+
+    loop {
+        buffer.clear();
+        let limit = MAX_LINE_LEN as u64 + 1;
+        match (&mut reader).take(limit).read_until(b'\n', &mut buffer) {
+            Ok(0) => break,
+            Ok(_) => line += 1,
+            Err(e) => return Err(AocError::io(STREAM, e)),
+        }
+        if buffer.len() > MAX_LINE_LEN && !buffer.ends_with(b"\n") {
+            return Err(AocError::parse(
+                line,
+                1,
+                format!("Calibration line is longer than {MAX_LINE_LEN} bytes"),
+            ));
+        }
+
+        let text = std::str::from_utf8(&buffer).map_err(|e| {
+            let valid = String::from_utf8_lossy(&buffer[..e.valid_up_to()]);
+            AocError::parse(
+                line,
+                valid.chars().count() + 1,
+                "Calibration line is not UTF-8",
+            )
+        })?;
+        let text = text.strip_suffix('\n').unwrap_or(text);
+
+        let report = LineReport::new(line, dictionary.tokens_in(text, rule.radix), rule);
+        let value = report
+            .value
+            .ok_or_else(|| AocError::parse(line, 1, "Calibration line has no digits"))?;
+        total = total
+            .checked_add(value)
+            .ok_or_else(|| AocError::overflow("calibration total"))?;
+    }
+
+    T::try_from(total).map_err(|_| AocError::overflow("calibration total"))
+}
+
+// the calibration total reading both digits and the dictionary's words
+pub fn calibrate(text: &str, dictionary: &Dictionary) -> Result<u32> {
+    CalibrationReport::new(text, dictionary).total()
+}
+
+// any calibration rule, with the total in whichever integer type it must fit
+pub fn calibrate_with<T: TryFrom<u128>>(
+    text: &str,
+    dictionary: &Dictionary,
+    rule: Rule,
+) -> Result<T> {
+    CalibrationReport::with_rule(text, dictionary, rule).total()
+}
+
+// like `calibrate_with`, also reading words up to `max_distance` edits away
+pub fn calibrate_fuzzy<T: TryFrom<u128>>(
+    text: &str,
+    dictionary: &Dictionary,
+    rule: Rule,
+    max_distance: usize,
+) -> Result<T> {
+    CalibrationReport::fuzzy(text, dictionary, rule, max_distance).total()
+}
+
+pub fn process_calibration_part2(file_contents: String, trie: &Trie<u32>) -> Result<u32> {
+    calibrate(&file_contents, &Dictionary::from_trie(trie.clone()))
+}
+
+pub struct Day1 {
+    calibration: String,
+}
+
+impl Solution for Day1 {
+    fn parse(input: &Input) -> Result<Self> {
+        Ok(Day1 {
+            calibration: input.as_str().to_owned(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(process_calibration_reader(self.calibration.as_bytes())?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(calibrate(&self.calibration, &Dictionary::english())?.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
+    use aoc_core::trie::Trie;
     use std::sync::OnceLock;
-    use trie::Trie;
 
     fn init_trie() -> &'static Trie<u32> {
         static TRIE: OnceLock<Trie<u32>> = OnceLock::new();
         TRIE.get_or_init(|| {
             let mut t: Trie<u32> = Trie::new();
-            t.add_string(&"one", 1);
-            t.add_string(&"two", 2);
-            t.add_string(&"three", 3);
-            t.add_string(&"four", 4);
-            t.add_string(&"five", 5);
-            t.add_string(&"six", 6);
-            t.add_string(&"seven", 7);
-            t.add_string(&"eight", 8);
-            t.add_string(&"nine", 9);
+            t.add_string("one", 1).unwrap();
+            t.add_string("two", 2).unwrap();
+            t.add_string("three", 3).unwrap();
+            t.add_string("four", 4).unwrap();
+            t.add_string("five", 5).unwrap();
+            t.add_string("six", 6).unwrap();
+            t.add_string("seven", 7).unwrap();
+            t.add_string("eight", 8).unwrap();
+            t.add_string("nine", 9).unwrap();
 
             t
         })
@@ -152,9 +681,7 @@ mod tests {
     fn part2_final() {
         let start = std::time::Instant::now();
 
-        let mut file = File::open("src/fixtures/day1.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
+        let file_contents = crate::fixture(1).unwrap().into_string();
 
         let number = process_calibration_part2(file_contents, init_trie());
 
@@ -191,6 +718,319 @@ zoneight234
         assert_eq!(281, number.unwrap());
     }
 
+    #[test]
+    fn number_word_overlaps() {
+        let words = Dictionary::from_trie(init_trie().clone());
+
+        let lines: [(&str, &[u32]); 9] = [
+            ("eightwothree", &[8, 2, 3]),
+            ("xtwone3four", &[2, 1, 3, 4]),
+            ("zoneight234", &[1, 8, 2, 3, 4]),
+            ("4nineeightseven2", &[4, 9, 8, 7, 2]),
+            ("7pqrstsixteen", &[7, 6]),
+            ("xrdnlbmtdeightone3threeeighttwo", &[8, 1, 3, 3, 8, 2]),
+            ("bnnqzcfoneeight2hhdfkrrqzt", &[1, 8, 2]),
+            ("342tlmjgtfcnine", &[3, 4, 2, 9]),
+            ("sevenineighthreeightwone", &[7, 9, 8, 3, 8, 2, 1]),
+        ];
+
+        for (line, numbers) in lines {
+            assert_eq!(words.digits(line), numbers, "{line}");
+        }
+
+        let test = "twone\noneight\neighthree\nsevenine".to_owned();
+        let number = process_calibration_part2(test, init_trie());
+
+        assert_eq!(21 + 18 + 83 + 79, number.unwrap());
+    }
+
+    #[test]
+    fn dictionaries() {
+        let english = Dictionary::english();
+        assert_eq!(english.words().len(), 9);
+        assert_eq!(calibrate("xtwone3four", &english).unwrap(), 24);
+
+        let spanish = Dictionary::spanish();
+        assert_eq!(spanish.digits("dosieteocho"), [2, 7, 8]);
+        assert_eq!(calibrate("uno2\ncuatrocinco\n", &spanish).unwrap(), 12 + 45);
+
+        let german = Dictionary::german();
+        assert_eq!(german.digits("fünfzweinsieben"), [5, 2, 1, 7]);
+        assert_eq!(calibrate("achtundneunzig", &german).unwrap(), 89);
+
+        let french = Dictionary::french();
+        assert_eq!(french.digits("deuxtroisept"), [2, 3, 7]);
+        assert_eq!(calibrate("neuf\nhuit7", &french).unwrap(), 99 + 87);
+
+        assert!(calibrate("one", &german).is_err());
+    }
+
+    #[test]
+    fn multi_digit_words() {
+        let dictionary = Dictionary::english()
+            .with_words([
+                ("twelve", 12),
+                ("twenty", 20),
+                ("hundred", 100),
+                ("zero", 0),
+            ])
+            .unwrap();
+
+        assert_eq!(dictionary.words().len(), 13);
+        assert_eq!(dictionary.digits("twelve"), [1, 2]);
+        assert_eq!(dictionary.digits("twentyone"), [2, 0, 1]);
+        assert_eq!(dictionary.digits("7hundred"), [7, 1, 0, 0]);
+        assert_eq!(dictionary.digits("zero"), [0]);
+
+        assert_eq!(calibrate("twelve", &dictionary).unwrap(), 12);
+        assert_eq!(calibrate("xtwentyx", &dictionary).unwrap(), 20);
+        assert_eq!(calibrate("three hundred", &dictionary).unwrap(), 30);
+
+        let custom = Dictionary::new([("dozen".to_owned(), 12), ("score".to_owned(), 20)]).unwrap();
+        assert_eq!(custom.words().len(), 2);
+        assert_eq!(calibrate("a dozen eggs, 4 score", &custom).unwrap(), 10);
+        assert!(custom.digits("one").is_empty());
+
+        let replaced = Dictionary::english().with_words([("one", 11)]).unwrap();
+        assert_eq!(replaced.words().len(), 9);
+        assert_eq!(calibrate("one", &replaced).unwrap(), 11);
+
+        assert!(matches!(
+            Dictionary::new([("", 1)]),
+            Err(AocError::InvalidState { .. })
+        ));
+    }
+
+    #[test]
+    fn calibration_report() {
+        let test = "two1nine\nxtwone3four\nzoneight\nnothing\n";
+        let report = CalibrationReport::new(test, &Dictionary::english());
+
+        assert_eq!(report.lines.len(), 4);
+        assert_eq!(
+            report.lines[0].tokens,
+            [
+                Token {
+                    start: 0,
+                    end: 3,
+                    text: "two".to_owned(),
+                    value: 2,
+                    distance: 0
+                },
+                Token {
+                    start: 3,
+                    end: 4,
+                    text: "1".to_owned(),
+                    value: 1,
+                    distance: 0
+                },
+                Token {
+                    start: 4,
+                    end: 8,
+                    text: "nine".to_owned(),
+                    value: 9,
+                    distance: 0
+                },
+            ]
+        );
+
+        let line = &report.lines[2];
+        assert_eq!(
+            (line.line, line.first.as_slice(), line.last.as_slice()),
+            (3, [1].as_slice(), [8].as_slice())
+        );
+        assert_eq!(line.value, Some(18));
+
+        let line = &report.lines[3];
+        assert!(line.tokens.is_empty());
+        assert!(line.first.is_empty() && line.last.is_empty());
+        assert_eq!(line.value, None);
+        assert!(matches!(
+            report.total::<u32>(),
+            Err(AocError::Parse { line: 4, .. })
+        ));
+
+        let table = report.table();
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!(rows[0], " line  value first  last  tokens");
+        assert_eq!(rows[2], "    2     24     2     4  two@1 one@3 3@6 four@7");
+        assert_eq!(rows[4], "    4      -     -     -  ");
+        assert!(rows[5].starts_with("total: line 4"));
+
+        let report = CalibrationReport::new("two1nine\nxtwone3four", &Dictionary::english());
+        assert!(report.table().ends_with("total 53\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.json().unwrap()).unwrap();
+        assert_eq!(json["lines"][1]["line"], 2);
+        assert_eq!(json["lines"][1]["value"], 24);
+        assert_eq!(json["lines"][1]["tokens"][1]["text"], "one");
+        assert_eq!(json["lines"][1]["tokens"][1]["start"], 3);
+        assert_eq!(json["lines"][1]["tokens"][1]["end"], 6);
+    }
+
+    #[test]
+    fn calibration_rules() {
+        let english = Dictionary::english();
+        let test = "1abc2x3\nfour9eightseven2\n7\n";
+
+        assert_eq!(
+            calibrate_with::<u32>(test, &english, Rule::default()).unwrap(),
+            13 + 42 + 77
+        );
+
+        let two = Rule::new(2, 10).unwrap();
+        assert_eq!(two.value(&[1, 2, 3]), Some(1223));
+        assert_eq!(two.value(&[7]), Some(77));
+        assert_eq!(two.value(&[]), None);
+        assert_eq!(
+            calibrate_with::<u64>(test, &english, two).unwrap(),
+            1223 + 4972 + 77
+        );
+
+        let report = CalibrationReport::with_rule(test, &english, two);
+        assert_eq!(report.lines[1].first, [4, 9]);
+        assert_eq!(report.lines[1].last, [7, 2]);
+        assert!(report
+            .table()
+            .contains("    2   4972    49    72  four@0 9@4"));
+
+        let hex = Rule::new(1, 16).unwrap();
+        let hex_words = Dictionary::hexadecimal();
+        assert_eq!(calibrate_with::<u32>("xyz\n", &hex_words, hex).ok(), None);
+        assert_eq!(
+            calibrate_with::<u32>("0xff", &hex_words, hex).unwrap(),
+            0x0f
+        );
+        assert_eq!(
+            calibrate_with::<u32>("twelve-Z-9", &hex_words, hex).unwrap(),
+            0xc9
+        );
+        assert!(calibrate_with::<u32>("twelve", &english, Rule::default()).is_err());
+
+        let octal = Rule::new(3, 8).unwrap();
+        let octal_words = Dictionary::octal();
+        assert_eq!(octal_words.words().len(), 8);
+        assert_eq!(
+            calibrate_with::<u32>("sevenzero 9 1 6three", &octal_words, octal).unwrap(),
+            0o701_163
+        );
+
+        assert!(matches!(
+            Rule::new(0, 10),
+            Err(AocError::InvalidState { .. })
+        ));
+        assert!(matches!(
+            Rule::new(1, 37),
+            Err(AocError::InvalidState { .. })
+        ));
+        assert!(Rule::new(19, 10).is_ok());
+        assert!(Rule::new(20, 10).is_err());
+        assert!(matches!(
+            Rule::new(usize::MAX, 10),
+            Err(AocError::InvalidState { .. })
+        ));
+    }
+
+    #[test]
+    fn radix_letters_inside_words() {
+        let hex_words = Dictionary::hexadecimal();
+
+        let tokens = hex_words.tokens_in("fifteen", 16);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!((tokens[0].text.as_str(), tokens[0].value), ("fifteen", 15));
+
+        let hex = Rule::new(1, 16).unwrap();
+        assert_eq!(
+            calibrate_with::<u32>("tenfifteen", &hex_words, hex).unwrap(),
+            0xaf
+        );
+        // letters outside any word are still digits
+        assert_eq!(
+            calibrate_with::<u32>("c-eleven-d", &hex_words, hex).unwrap(),
+            0xcd
+        );
+
+        let line = "fifteen".repeat(MAX_LINE_LEN / 7);
+        let tokens = hex_words.tokens_in(&line, 16);
+        assert_eq!(tokens.len(), MAX_LINE_LEN / 7);
+        assert!(tokens.iter().all(|t| t.value == 15));
+        let line = "nien".repeat(MAX_LINE_LEN / 4);
+        let tokens = Dictionary::english().fuzzy_tokens_in(&line, 10, 1);
+        assert_eq!(tokens.len(), MAX_LINE_LEN / 4);
+        assert!(tokens.iter().all(|t| t.value == 9));
+    }
+
+    #[test]
+    fn wide_totals() {
+        let rule = Rule::new(9, 10).unwrap();
+        let line = "123456789x987654321\n";
+        let text = line.repeat(5);
+
+        assert!(matches!(
+            calibrate_with::<u32>(&text, &Dictionary::english(), rule),
+            Err(AocError::Overflow { .. })
+        ));
+        assert_eq!(
+            calibrate_with::<u64>(&text, &Dictionary::english(), rule).unwrap(),
+            5 * 123_456_789_987_654_321
+        );
+
+        let rule = Rule::new(19, 10).unwrap();
+        let text = "9".repeat(38).repeat(2) + "\n";
+        assert!(calibrate_with::<u64>(&text, &Dictionary::english(), rule).is_err());
+        assert_eq!(
+            calibrate_with::<u128>(&text, &Dictionary::english(), rule).unwrap(),
+            10u128.pow(38) - 1
+        );
+    }
+
+    #[test]
+    fn fuzzy_calibration() {
+        let english = Dictionary::english();
+        let rule = Rule::default();
+        let test = "xnien4\nsevn\nthre3five\nfuor\n";
+
+        assert!(calibrate_with::<u32>(test, &english, rule).is_err());
+        assert!(calibrate_fuzzy::<u32>(test, &english, rule, 0).is_err());
+        assert_eq!(
+            calibrate_fuzzy::<u32>(test, &english, rule, 1).unwrap(),
+            94 + 77 + 35 + 44
+        );
+
+        let report = CalibrationReport::fuzzy(test, &english, rule, 1);
+        let accepted: Vec<_> = report
+            .fuzzy_matches()
+            .map(|(line, t)| (line, t.text.as_str(), t.value, t.distance))
+            .collect();
+        assert_eq!(
+            accepted,
+            [
+                (1, "nien", 9, 1),
+                (2, "sevn", 7, 1),
+                (3, "thre", 3, 1),
+                (4, "fuor", 4, 1)
+            ]
+        );
+        assert!(report
+            .table()
+            .contains("    1     94     9     4  nien~1@1 4@5"));
+
+        // exact words keep overlapping, misspellings never take their letters
+        let tokens = english.fuzzy_tokens_in("twone8", 10, 1);
+        assert!(tokens.iter().all(|t| t.distance == 0));
+        assert_eq!(tokens.len(), 3);
+
+        // too few letters left of the word for it to count
+        assert!(english.fuzzy_tokens_in("on", 10, 1).is_empty());
+        assert!(english.fuzzy_tokens_in("xy", 10, 2).is_empty());
+
+        let answer = crate::fixture(1).unwrap().into_string();
+        assert_eq!(
+            calibrate_fuzzy::<u32>(&answer, &english, rule, 0).unwrap(),
+            54985
+        );
+    }
+
     #[test]
     fn part_1_smoke() {
         let test_input = r#"1abc2
@@ -203,14 +1043,150 @@ treb7uchet"#
         assert_eq!(number, 142);
     }
 
+    #[test]
+    fn line_without_digits() {
+        let err = process_calibration("1abc2\npqrstu\n".to_owned()).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+
+        let err = process_calibration_part2("two1\nxyz".to_owned(), init_trie()).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+
+        assert_eq!(process_line(&[]), None);
+        assert_eq!(process_line(&[7]), Some(77));
+    }
+
     #[test]
     fn part1() {
-        let mut file = File::open("src/fixtures/day1.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
+        let file_contents = crate::fixture(1).unwrap().into_string();
 
         let number = process_calibration(file_contents);
 
         assert_eq!(number.unwrap(), 55130_u32);
     }
+
+    // hands out `size` bytes per read, then fails instead of ending if `broken`
+    struct Trickle<'a> {
+        data: &'a [u8],
+        size: usize,
+        broken: bool,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.fill_buf()?.len().min(buf.len());
+            buf[..read].copy_from_slice(&self.data[..read]);
+            self.consume(read);
+            Ok(read)
+        }
+    }
+
+    impl BufRead for Trickle<'_> {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            if self.broken && self.data.is_empty() {
+                return Err(std::io::Error::other("disk went away"));
+            }
+            Ok(&self.data[..self.size.min(self.data.len())])
+        }
+
+        fn consume(&mut self, amount: usize) {
+            self.data = &self.data[amount..];
+        }
+    }
+
+    #[test]
+    fn streaming_calibration() {
+        let start = std::time::Instant::now();
+        let file_contents = crate::fixture(1).unwrap().into_string();
+        let english = Dictionary::english();
+
+        assert_eq!(
+            process_calibration_reader(file_contents.as_bytes()).unwrap(),
+            55130
+        );
+        assert_eq!(
+            calibrate_reader::<u32>(file_contents.as_bytes(), &english, Rule::default()).unwrap(),
+            54985
+        );
+
+        // tiny buffers split lines and words across reads
+        for size in [1, 2, 3, 7] {
+            let trickle = || Trickle {
+                data: file_contents.as_bytes(),
+                size,
+                broken: false,
+            };
+            assert_eq!(process_calibration_reader(trickle()).unwrap(), 55130);
+
+            let total: u32 = calibrate_reader(trickle(), &english, Rule::default()).unwrap();
+            assert_eq!(total, 54985);
+        }
+
+        // no trailing newline, and nothing at all
+        assert_eq!(
+            process_calibration_reader(&b"1abc2\ntreb7uchet"[..]).unwrap(),
+            12 + 77
+        );
+        assert_eq!(process_calibration_reader(&b""[..]).unwrap(), 0);
+        let sample = "two1nine\nabcone2threexyz\nzoneight234";
+        assert_eq!(
+            calibrate_reader::<u32>(sample.as_bytes(), &english, Rule::default()).unwrap(),
+            calibrate(sample, &english).unwrap()
+        );
+
+        // the same rules as the in-memory version
+        let hex = Rule::new(2, 16).unwrap();
+        let text = "fifteen1ten\n2a3b\n";
+        assert_eq!(
+            calibrate_reader::<u128>(text.as_bytes(), &Dictionary::hexadecimal(), hex).unwrap(),
+            calibrate_with::<u128>(text, &Dictionary::hexadecimal(), hex).unwrap()
+        );
+
+        println!("Process in: {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn streaming_errors() {
+        let english = Dictionary::english();
+
+        let err = process_calibration_reader(&b"1abc2\npqrstu\n7"[..]).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+        let err = process_calibration_reader(&b"1\n\n"[..]).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+
+        let err =
+            calibrate_reader::<u32>(&b"two1\nxyz\n"[..], &english, Rule::default()).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+
+        let err =
+            calibrate_reader::<u32>(&b"1\n\xc3one\xff"[..], &english, Rule::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 1,
+                ..
+            }
+        ));
+
+        let err =
+            calibrate_reader::<u8>(&b"99\n99\n99\n"[..], &english, Rule::default()).unwrap_err();
+        assert!(matches!(err, AocError::Overflow { .. }));
+
+        let longest = format!("7{}\n", "x".repeat(MAX_LINE_LEN - 1));
+        let read = |text: &str| calibrate_reader::<u32>(text.as_bytes(), &english, Rule::default());
+        assert_eq!(read(&longest).unwrap(), 77);
+        assert_eq!(read(longest.trim_end()).unwrap(), 77);
+        let err = read(&format!("1\n{}", longest.replace('\n', "x\n"))).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+
+        let broken = || Trickle {
+            data: b"12\n3",
+            size: 2,
+            broken: true,
+        };
+        let err = process_calibration_reader(broken()).unwrap_err();
+        assert!(matches!(err, AocError::Io { .. }));
+        let err = calibrate_reader::<u32>(broken(), &english, Rule::default()).unwrap_err();
+        assert!(matches!(err, AocError::Io { .. }));
+    }
 }
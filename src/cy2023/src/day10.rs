@@ -208,6 +208,13 @@
 
 use std::collections::HashSet;
 
+use tracing::{debug, enabled, trace, Level};
+
+use aoc_core::error::{AocError, Result};
+use aoc_core::grid::{Delta, Grid, Point, DOWN, LEFT, RIGHT, UP};
+use aoc_core::input::Input;
+use aoc_core::solution::{Answer, Solution};
+
 #[derive(Debug, Copy, Clone)]
 enum Dir {
     Down,
@@ -216,135 +223,142 @@ enum Dir {
     Right,
 }
 
-fn get_start_pipe_type(pipes: &Vec<bool>, map: &[u8], start_pos: usize, width: usize) -> u8 {
-    let rt = if start_pos + 1 < map.len() - 1 {
-        Some(start_pos + 1)
-    } else {
-        None
-    };
+impl Dir {
+    fn delta(self) -> Delta {
+        match self {
+            Dir::Down => DOWN,
+            Dir::Up => UP,
+            Dir::Left => LEFT,
+            Dir::Right => RIGHT,
+        }
+    }
+}
 
-    let lt = start_pos.checked_sub(1);
-    let up = start_pos.checked_sub(width + 1);
-    let dn = if start_pos + width + 1 < map.len() {
-        Some(start_pos + width + 1)
-    } else {
-        None
+fn get_start_pipe_type(pipes: &Grid<bool>, map: &Grid<u8>, start_pos: Point) -> Result<u8> {
+    // which tiles are pointing at S?
+    let points_at_start = |delta: Delta, tiles: &[u8]| {
+        map.offset(start_pos, delta)
+            .is_some_and(|p| pipes[p] && tiles.contains(&map[p]))
     };
 
-    // which tiles are pointing at S?
     let mut start_type = 0u8;
 
-    unsafe {
-        if up.is_some_and(|up| {
-            *pipes.get_unchecked(up) && (map[up] == b'|' || map[up] == b'F' || map[up] == b'7')
-        }) {
-            // above
-            start_type |= 0x1;
-        }
-        if rt.is_some_and(|rt| {
-            *pipes.get_unchecked(rt) && (map[rt] == b'7' || map[rt] == b'-' || map[rt] == b'J')
-        }) {
-            // right
-            start_type |= 0x2;
-        }
-        if dn.is_some_and(|dn| {
-            *pipes.get_unchecked(dn) && (map[dn] == b'|' || map[dn] == b'L' || map[dn] == b'J')
-        }) {
-            // down
-            start_type |= 0x4;
-        }
-        if lt.is_some_and(|lt| {
-            *pipes.get_unchecked(lt) && (map[lt] == b'F' || map[lt] == b'-' || map[lt] == b'L')
-        }) {
-            // left
-            start_type |= 0x8;
-        }
+    if points_at_start(UP, b"|F7") {
+        // above
+        start_type |= 0x1;
+    }
+    if points_at_start(RIGHT, b"7-J") {
+        // right
+        start_type |= 0x2;
+    }
+    if points_at_start(DOWN, b"|LJ") {
+        // down
+        start_type |= 0x4;
+    }
+    if points_at_start(LEFT, b"F-L") {
+        // left
+        start_type |= 0x8;
     }
 
     // above and right
     if start_type & 0x3 == 0x3 {
-        return b'L';
+        Ok(b'L')
     }
     // above and down
     else if start_type & 0x5 == 0x5 {
-        return b'|';
+        Ok(b'|')
     }
     // above and left
     else if start_type & 0x9 == 0x9 {
-        return b'J';
+        Ok(b'J')
     }
     // right and down
     else if start_type & 0x6 == 0x6 {
-        return b'F';
+        Ok(b'F')
     }
     // right and left
     else if start_type & 0xA == 0xA {
-        return b'-';
+        Ok(b'-')
     }
     // left and down
     else if start_type & 0xC == 0xC {
-        return b'7';
+        Ok(b'7')
     } else {
-        panic!("not good")
+        Err(AocError::invalid(format!(
+            "S at {start_pos:?} does not join two pipes of the loop"
+        )))
     }
 }
 
-pub fn solve2(map: &mut [u8]) -> usize {
-    let start = map.iter().position(|&c| c == b'S').unwrap();
-    let width = map.iter().position(|&c| c == b'\n').unwrap();
+fn start_position(map: &Grid<u8>) -> Result<Point> {
+    map.position(|&c| c == b'S')
+        .ok_or_else(|| AocError::invalid("Pipe map has no starting tile"))
+}
+
+fn find_start(map: &Grid<u8>) -> Result<(Point, Point, Dir)> {
+    let start = start_position(map)?;
 
-    let (mut pos, mut dir) = if matches!(map[start + width + 1], b'|' | b'L' | b'J') {
-        (start + width + 1, Dir::Down)
-    } else if matches!(map[start - width - 1], b'|' | b'F' | b'7') {
-        (start - width - 1, Dir::Up)
-    } else {
+    let connects = |delta: Delta, tiles: &[u8]| {
+        map.offset(start, delta)
+            .filter(|&p| tiles.contains(&map[p]))
+    };
+
+    let (pos, dir) = if let Some(down) = connects(DOWN, b"|LJ") {
+        (down, Dir::Down)
+    } else if let Some(up) = connects(UP, b"|F7") {
+        (up, Dir::Up)
+    } else if let Some(left) = connects(LEFT, b"-LF") {
         // must be left and right for a loop to exist if up and down were not found
-        (start - 1, Dir::Left)
+        (left, Dir::Left)
+    } else {
+        return Err(AocError::invalid(format!(
+            "S at {start:?} is not connected to any pipe"
+        )));
     };
 
-    use std::str;
-
-    println!("S:{:?}, width:{:?}", &start, &width);
-    println!("start: {:?}", &(str::from_utf8(&[map[pos]]).unwrap(), dir));
-
-    let mut pipes = vec![false; map.len()];
-
-    // from starting S, follow all pipe connections, marking them as pipes in "pipes" vec
-    let _n = std::iter::repeat(())
-        .position(|_| unsafe {
-            *pipes.get_unchecked_mut(pos) = true;
-            match (map[pos], dir) {
-                (b'|', Dir::Down) => pos += width + 1,
-                (b'|', Dir::Up) => pos -= width + 1,
-                (b'-', Dir::Right) => pos += 1,
-                (b'-', Dir::Left) => pos -= 1,
-                (b'J', Dir::Right) | (b'L', Dir::Left) => {
-                    pos -= width + 1;
-                    dir = Dir::Up;
-                }
-                (b'7', Dir::Up) | (b'J', Dir::Down) => {
-                    pos -= 1;
-                    dir = Dir::Left;
-                }
-                (b'F', Dir::Up) | (b'L', Dir::Down) => {
-                    pos += 1;
-                    dir = Dir::Right;
-                }
-                (b'7', Dir::Right) | (b'F', Dir::Left) => {
-                    pos += width + 1;
-                    dir = Dir::Down;
-                }
-                (b'S', _) => return true,
-                _ => unreachable!(),
-            };
-            false
-        })
-        .unwrap();
+    debug!(?start, tile = %(map[pos] as char), ?dir, "found start");
+
+    Ok((start, pos, dir))
+}
+
+// follows the loop out of S until it arrives back at S, visiting every tile on
+// the way (S last); returns the length of the loop
+fn walk_loop(map: &Grid<u8>, mut visit: impl FnMut(Point)) -> Result<usize> {
+    let (_, mut pos, mut dir) = find_start(map)?;
+    let mut steps = 1;
+
+    loop {
+        visit(pos);
+
+        dir = match (map[pos], dir) {
+            (b'|', Dir::Down) | (b'7', Dir::Right) | (b'F', Dir::Left) => Dir::Down,
+            (b'|', Dir::Up) | (b'J', Dir::Right) | (b'L', Dir::Left) => Dir::Up,
+            (b'-', Dir::Right) | (b'F', Dir::Up) | (b'L', Dir::Down) => Dir::Right,
+            (b'-', Dir::Left) | (b'7', Dir::Up) | (b'J', Dir::Down) => Dir::Left,
+            (b'S', _) => return Ok(steps),
+            (tile, dir) => {
+                return Err(AocError::invalid(format!(
+                    "Loop is broken: heading {dir:?} into '{}' at {pos:?}",
+                    tile as char
+                )))
+            }
+        };
 
-    let start_effective_pipe_type = get_start_pipe_type(&pipes, map, start, width);
-    unsafe {
-        *map.get_unchecked_mut(start) = start_effective_pipe_type;
+        pos = map.offset(pos, dir.delta()).ok_or_else(|| {
+            AocError::invalid(format!("Loop is broken: runs off the map at {pos:?}"))
+        })?;
+        steps += 1;
     }
+}
+
+pub fn solve2(map: &mut Grid<u8>) -> Result<usize> {
+    let mut pipes = Grid::new(map.width(), map.height(), false);
+
+    // from starting S, follow all pipe connections, marking them as pipes in "pipes" grid
+    walk_loop(map, |pos| pipes[pos] = true)?;
+
+    let start = start_position(map)?;
+    map[start] = get_start_pipe_type(&pipes, map, start)?;
 
     #[derive(Clone, Debug)]
     enum Start {
@@ -375,9 +389,8 @@ pub fn solve2(map: &mut [u8]) -> usize {
     let mut state = State::Area(Mode::Outside);
     let inside_pos = map
         .iter()
-        .enumerate()
-        .filter(|(pos, tile)| unsafe {
-            let is_pipe = *pipes.get_unchecked(*pos);
+        .filter(|(pos, tile)| {
+            let is_pipe = pipes[*pos];
 
             match &state {
                 State::Area(m) => {
@@ -389,10 +402,10 @@ pub fn solve2(map: &mut [u8]) -> usize {
                         } else if matches!(tile, b'F') {
                             state = State::Barrier(Start::F, m.clone());
                         }
-                        return false;
+                        false
                     } else {
                         // if inside, return true to count area
-                        return matches!(m, Mode::Inside);
+                        matches!(m, Mode::Inside)
                     }
                 }
                 State::Barrier(s, m) => {
@@ -414,110 +427,81 @@ pub fn solve2(map: &mut [u8]) -> usize {
                             }
                         }
                     }
-                    return false;
+                    false
                 }
             }
         })
+        .map(|(pos, _)| pos)
         // .inspect(|x| println!("x: {:?}", &x))
-        .collect::<Vec<(usize, &u8)>>();
+        .collect::<Vec<Point>>();
 
     let inside_area = inside_pos.len();
-    println!("Inside area: {}", inside_area);
+    debug!(inside_area, "enclosed tiles counted");
 
-    print_map(width, map, &pipes, &inside_pos);
+    if enabled!(Level::TRACE) {
+        trace!("\n{}", render_map(map, &pipes, &inside_pos));
+    }
 
-    inside_area
+    Ok(inside_area)
 }
 
-pub fn print_map(width: usize, map: &[u8], pipes: &Vec<bool>, inside_tiles: &Vec<(usize, &u8)>) {
-    let inside_hash: HashSet<&usize> = HashSet::from_iter(inside_tiles.iter().map(|(t, _)| t));
+// the loop with the enclosed tiles marked, framed top and bottom
+pub fn render_map(map: &Grid<u8>, pipes: &Grid<bool>, inside_tiles: &[Point]) -> String {
+    let inside_hash: HashSet<&Point> = HashSet::from_iter(inside_tiles.iter());
 
-    std::iter::repeat("^")
-        .take(width)
-        .for_each(|c| print!("{}", c));
-    println!();
+    let mut out = "^".repeat(map.width());
+    out.push('\n');
 
-    pipes
-        .iter()
-        .zip(map)
-        .enumerate()
-        .for_each(|(pos, (is_pipe, tile))| {
-            let output = if *is_pipe {
-                *tile
+    map.iter().for_each(|(pos, tile)| {
+        let output = if pipes[pos] {
+            *tile
+        } else {
+            if inside_hash.contains(&pos) {
+                183
             } else {
-                if inside_hash.contains(&pos) {
-                    183
-                } else if *tile == b'\n' {
-                    b'\n'
-                } else {
-                    160
-                }
-            };
-
-            match output {
-                b'\n' => println!("|"),
-                _ => print!("{}", output as char),
+                160
             }
-        });
+        };
 
-    println!();
+        out.push(output as char);
+        if pos.1 == map.width() - 1 {
+            out.push_str("|\n");
+        }
+    });
 
-    std::iter::repeat("_")
-        .take(width)
-        .for_each(|c| print!("{}", c));
+    out.push('\n');
+    out.push_str(&"_".repeat(map.width()));
+    out
+}
 
-    println!();
+pub fn solve(map: &Grid<u8>) -> Result<usize> {
+    Ok(walk_loop(map, |_| {})? / 2)
 }
 
-pub fn solve(map: &[u8]) -> usize {
-    let start = map.iter().position(|&c| c == b'S').unwrap();
-    let width = map.iter().position(|&c| c == b'\n').unwrap();
+pub struct Day10 {
+    map: Grid<u8>,
+}
 
-    let (mut pos, mut dir) = if matches!(map[start + width + 1], b'|' | b'L' | b'J') {
-        (start + width + 1, Dir::Down)
-    } else if matches!(map[start - width - 1], b'|' | b'F' | b'7') {
-        (start - width - 1, Dir::Up)
-    } else {
-        // must be left and right for a loop to exist if up and down were not found
-        (start - 1, Dir::Left)
-    };
+impl Solution for Day10 {
+    fn parse(input: &Input) -> Result<Self> {
+        let map = input.byte_grid()?;
 
-    use std::str;
-
-    println!("S:{:?}, width:{:?}", &start, &width);
-    println!("start: {:?}", &(str::from_utf8(&[map[pos]]).unwrap(), dir));
-
-    (1 + std::iter::repeat(())
-        .position(|_| {
-            // println!("{:?}", &(str::from_utf8(&[map[pos]]).unwrap(), dir));
-            match (map[pos], dir) {
-                (b'|', Dir::Down) => pos += width + 1,
-                (b'|', Dir::Up) => pos -= width + 1,
-                (b'-', Dir::Right) => pos += 1,
-                (b'-', Dir::Left) => pos -= 1,
-                (b'J', Dir::Right) | (b'L', Dir::Left) => {
-                    pos -= width + 1;
-                    dir = Dir::Up;
-                }
-                (b'7', Dir::Up) | (b'J', Dir::Down) => {
-                    pos -= 1;
-                    dir = Dir::Left;
-                }
-                (b'F', Dir::Up) | (b'L', Dir::Down) => {
-                    pos += 1;
-                    dir = Dir::Right;
-                }
-                (b'7', Dir::Right) | (b'F', Dir::Left) => {
-                    pos += width + 1;
-                    dir = Dir::Down;
-                }
-                (b'S', _) => return true,
-                _ => unreachable!(),
-            };
-            false
-        })
-        .unwrap())
-        / 2
+        start_position(&map)?;
+        if map.height() < 2 {
+            return Err(AocError::invalid("Pipe map must have more than one row"));
+        }
+
+        Ok(Day10 { map })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(solve(&self.map)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        let mut map = self.map.clone();
+        Ok(solve2(&mut map)?.into())
+    }
 }
 
 #[cfg(test)]
@@ -528,7 +512,7 @@ mod tests {
     fn day10_2_custom1() {
         let start = std::time::Instant::now();
 
-        let mut input = r#"...........
+        let mut input: Grid<u8> = r#"...........
 .S-------7.
 .|F-----7|.
 .||.....||.
@@ -537,13 +521,11 @@ FJ|.....||.
 |...|.|..|.
 |...|.L--J.
 L---J......"#
-            .to_owned();
+            .parse()
+            .unwrap();
 
-        let input = unsafe { input.as_bytes_mut() };
-
-        use std::str;
-        println!("{:?}", str::from_utf8(&input));
-        let area = solve2(input);
+        println!("{}", &input);
+        let area = solve2(&mut input).unwrap();
 
         assert_eq!(area, 9usize);
         println!("Process in: {:?}", start.elapsed());
@@ -552,10 +534,10 @@ L---J......"#
     #[test]
     fn day10_2_file() {
         let start = std::time::Instant::now();
-        let mut input = include_bytes!("fixtures/day10.txt").clone();
+        let mut input = crate::fixture(10).unwrap().byte_grid().unwrap();
 
         // let area = other_solve(input);
-        let area = solve2(input.as_mut_slice());
+        let area = solve2(&mut input).unwrap();
 
         assert_eq!(area, 325usize);
         println!("Process in: {:?}", start.elapsed());
@@ -564,7 +546,7 @@ L---J......"#
     #[test]
     fn day10_2_sample4() {
         let start = std::time::Instant::now();
-        let mut input = r#"FF7FSF7F7F7F7F7F---7
+        let mut input: Grid<u8> = r#"FF7FSF7F7F7F7F7F---7
 L|LJ||||||||||||F--J
 FL-7LJLJ||||||LJL-77
 F--JF--7||LJLJ7F7FJ-
@@ -574,12 +556,11 @@ L---JF-JLJ.||-FJLJJ7
 7-L-JL7||F7|L7F-7F7|
 L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L"#
-            .to_owned();
-        let input = unsafe { input.as_bytes_mut() };
+            .parse()
+            .unwrap();
 
-        // use std::str;
-        // println!("{:?}", str::from_utf8(&input));
-        let area = solve2(input);
+        // println!("{}", &input);
+        let area = solve2(&mut input).unwrap();
 
         assert_eq!(area, 10usize);
         println!("Process in: {:?}", start.elapsed());
@@ -588,7 +569,7 @@ L7JLJL-JLJLJL--JLJ.L"#
     #[test]
     fn day10_2_sample3() {
         let start = std::time::Instant::now();
-        let mut input = r#".F----7F7F7F7F-7....
+        let mut input: Grid<u8> = r#".F----7F7F7F7F-7....
 .|F--7||||||||FJ....
 .||.FJ||||||||L7....
 FJL7L7LJLJ||LJ.L-7..
@@ -598,13 +579,11 @@ L--J.L7...LJS7F-7L7.
 .....|FJLJ|FJ|F7|.LJ
 ....FJL-7.||.||||...
 ....L---J.LJ.LJLJ..."#
-            .to_owned();
-
-        let input = unsafe { input.as_bytes_mut() };
+            .parse()
+            .unwrap();
 
-        // use std::str;
-        // println!("{:?}", str::from_utf8(&input));
-        let area = solve2(input);
+        // println!("{}", &input);
+        let area = solve2(&mut input).unwrap();
 
         assert_eq!(area, 8usize);
         println!("Process in: {:?}", start.elapsed());
@@ -614,7 +593,7 @@ L--J.L7...LJS7F-7L7.
     fn day10_2_sample2() {
         let start = std::time::Instant::now();
 
-        let mut input = r#"...........
+        let mut input: Grid<u8> = r#"...........
 .S-------7.
 .|F-----7|.
 .||.....||.
@@ -623,12 +602,11 @@ L--J.L7...LJS7F-7L7.
 .|..|.|..|.
 .L--J.L--J.
 ..........."#
-            .to_owned();
-        let input = unsafe { input.as_bytes_mut() };
+            .parse()
+            .unwrap();
 
-        use std::str;
-        println!("{:?}", str::from_utf8(&input));
-        let area = solve2(input);
+        println!("{}", &input);
+        let area = solve2(&mut input).unwrap();
 
         assert_eq!(area, 4usize);
         println!("Process in: {:?}", start.elapsed());
@@ -637,9 +615,9 @@ L--J.L7...LJS7F-7L7.
     #[test]
     fn day10_file1() {
         let start = std::time::Instant::now();
-        let input = include_bytes!("fixtures/day10.txt");
+        let input = crate::fixture(10).unwrap().byte_grid().unwrap();
 
-        let furthest_tile = solve(input);
+        let furthest_tile = solve(&input).unwrap();
 
         assert_eq!(furthest_tile, 6846usize);
         println!("Process in: {:?}", start.elapsed());
@@ -654,13 +632,29 @@ L--J.L7...LJS7F-7L7.
 SJ.L7
 |F--J
 LJ..."#;
-        let input = input.as_bytes();
+        let input: Grid<u8> = input.parse().unwrap();
 
-        use std::str;
-        println!("{:?}", str::from_utf8(&input));
-        let furthest_tile = solve(input);
+        println!("{}", &input);
+        let furthest_tile = solve(&input).unwrap();
 
         assert_eq!(furthest_tile, 8usize);
         println!("Process in: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn broken_loop() {
+        let map: Grid<u8> = ".....\n.S-7.\n.|.|.\n.L-..\n.....".parse().unwrap();
+        let err = solve(&map).unwrap_err();
+        assert!(matches!(err, AocError::InvalidState { .. }));
+        assert!(err.to_string().contains("Loop is broken"));
+
+        let map: Grid<u8> = "...\n.S.\n...".parse().unwrap();
+        assert!(matches!(
+            solve2(&mut map.clone()),
+            Err(AocError::InvalidState { .. })
+        ));
+
+        let map: Grid<u8> = "...\n...".parse().unwrap();
+        assert!(solve(&map).is_err());
+    }
 }
@@ -97,17 +97,24 @@
 //
 // Starting with the same initial image, expand the universe according to these new rules, then find the length of the shortest path between every pair of galaxies. What is the sum of these lengths?
 
-use std::collections::HashSet;
+use tracing::debug;
 
-type Coordinate = (usize, usize);
+use aoc_core::error::{AocError, Result};
+use aoc_core::grid::{Grid, Point};
+use aoc_core::input::Input;
+use aoc_core::solution::{Answer, Solution};
+
+type Coordinate = Point;
 type GalacticCoordinates = Vec<Coordinate>;
 
+#[cfg(test)]
 #[derive(Debug, Clone)]
 struct WalkInfo {
     src_point: Coordinate,
     destinations: Vec<DestInfo>,
 }
 
+#[cfg(test)]
 #[derive(Debug, Clone)]
 struct SlopeInfo {
     x: usize,
@@ -116,19 +123,21 @@ struct SlopeInfo {
     y_sign: bool,
 }
 
+#[cfg(test)]
 #[derive(Debug, Clone)]
 struct DestInfo {
     dst_point: Coordinate,
     slope_pair: SlopeInfo,
 }
 
-type FnDistanceCalc = fn(GalacticCoordinates) -> usize;
+type FnDistanceCalc = fn(GalacticCoordinates) -> Result<usize>;
 
-fn solve(map: &[u8], expansion_factor: usize, calc_dist_fn: FnDistanceCalc) -> usize {
-    let expanded_map = expand_map(map, expansion_factor);
+fn solve(map: &Grid<u8>, expansion_factor: usize, calc_dist_fn: FnDistanceCalc) -> Result<usize> {
+    let expanded_map = expand_map(map, expansion_factor)?;
     calc_dist_fn(expanded_map)
 }
 
+#[cfg(test)]
 fn get_slope_pairs(expanded_map: GalacticCoordinates) -> Vec<WalkInfo> {
     let coord_len = expanded_map.len();
     let mut coord_window_start = 0;
@@ -140,10 +149,7 @@ fn get_slope_pairs(expanded_map: GalacticCoordinates) -> Vec<WalkInfo> {
         let src_x = expanded_map[coord_window_start].0;
         let src_y = expanded_map[coord_window_start].1;
 
-        for peer in coord_window_start + 1..expanded_map.len() {
-            let dst_x = expanded_map[peer].0;
-            let dst_y = expanded_map[peer].1;
-
+        for &(dst_x, dst_y) in &expanded_map[coord_window_start + 1..] {
             let mut slope_x = dst_x as isize - src_x as isize;
             let mut slope_y = dst_y as isize - src_y as isize;
 
@@ -151,17 +157,17 @@ fn get_slope_pairs(expanded_map: GalacticCoordinates) -> Vec<WalkInfo> {
             let y_sign = slope_y < 0;
 
             if slope_x >= slope_y && slope_y > 0 {
-                slope_x = slope_x / slope_y;
+                slope_x /= slope_y;
                 slope_y = 1;
             } else if slope_y >= slope_x && slope_x > 0 {
-                slope_y = slope_y / slope_x;
+                slope_y /= slope_x;
                 slope_x = 1;
             } else if x_sign && !y_sign {
                 if slope_x.abs() > slope_y {
                     slope_x = slope_x.abs() / slope_y;
                     slope_y = 1;
                 } else {
-                    slope_y = slope_y / slope_x.abs();
+                    slope_y /= slope_x.abs();
                     slope_x = 1;
                 }
             } else if !x_sign && y_sign {
@@ -169,7 +175,7 @@ fn get_slope_pairs(expanded_map: GalacticCoordinates) -> Vec<WalkInfo> {
                     slope_y = slope_y.abs() / slope_x;
                     slope_x = 1;
                 } else {
-                    slope_x = slope_x / slope_y.abs();
+                    slope_x /= slope_y.abs();
                     slope_y = 1;
                 }
             }
@@ -195,7 +201,8 @@ fn get_slope_pairs(expanded_map: GalacticCoordinates) -> Vec<WalkInfo> {
     walking_info
 }
 
-fn calculate_approx_distances(expanded_map: GalacticCoordinates) -> usize {
+#[cfg(test)]
+fn calculate_approx_distances(expanded_map: GalacticCoordinates) -> Result<usize> {
     fn walk_to_point(src: Coordinate, dst_info: &DestInfo) -> usize {
         let SlopeInfo {
             x_sign,
@@ -280,11 +287,7 @@ fn calculate_approx_distances(expanded_map: GalacticCoordinates) -> usize {
 
     let slopes = get_slope_pairs(expanded_map);
     let total_dests = slopes.iter().fold(0, |acc, s| acc + s.destinations.len());
-    println!(
-        "Begin walking {:?} slope entries with {:?} total slope destinations.",
-        slopes.len(),
-        total_dests
-    );
+    debug!(slopes = slopes.len(), total_dests, "begin walking");
 
     let walking_sum: usize = slopes.iter().fold(0, |sum, walk_info| {
         let WalkInfo {
@@ -294,77 +297,98 @@ fn calculate_approx_distances(expanded_map: GalacticCoordinates) -> usize {
 
         let distance_sum = dest.iter().fold(0, |sum, d| {
             let s = sum + walk_to_point((*src_x, *src_y), d);
-            println!("Completed walk from ({:?}, {:?}) to {:?}", src_x, src_y, d);
+            tracing::trace!(src = ?(src_x, src_y), dst = ?d, "completed walk");
             s
         });
 
         distance_sum + sum
     });
 
-    walking_sum
+    Ok(walking_sum)
 }
 
-type Galaxy = (usize, usize);
-
-fn expand_map(map: &[u8], expansion_factor: usize) -> GalacticCoordinates {
-    let n = map
+// the walk above only ever steps one row or column at a time, so the path
+// length between two galaxies is their manhattan distance
+fn calculate_manhattan_distances(expanded_map: GalacticCoordinates) -> Result<usize> {
+    expanded_map
         .iter()
-        .position(|&c| c == b'\n')
-        .expect("format defines newline terminator");
-    let num_newlines = map.iter().filter(|c| **c == b'\n').count();
-    let m = (map.len() - num_newlines) / n;
-
-    let mut used_rows = HashSet::new();
-    let mut used_cols = HashSet::new();
-
-    let mut new_lines = 0usize;
-    let mut galaxies: Vec<Galaxy> = map.iter().enumerate().fold(vec![], |mut acc, (ix, ch)| {
-        let ix_mod = ix - new_lines;
-        let (row, col) = (ix_mod / m, ix_mod % m);
-        match *ch {
-            b'#' => {
-                used_rows.insert(row);
-                used_cols.insert(col);
-
-                acc.push((row, col));
-            }
-            b'\n' => new_lines += 1,
-            _ => {}
-        }
-        acc
-    });
+        .enumerate()
+        .flat_map(|(ix, src)| expanded_map[ix + 1..].iter().map(move |dst| (src, dst)))
+        .try_fold(0usize, |sum, (src, dst)| {
+            src.0
+                .abs_diff(dst.0)
+                .checked_add(src.1.abs_diff(dst.1))
+                .and_then(|distance| sum.checked_add(distance))
+        })
+        .ok_or_else(|| AocError::overflow("sum of galaxy distances"))
+}
 
-    let vacant_rows: Vec<usize> = (0..m).filter(|col| !used_rows.contains(&col)).collect();
-    let vacant_cols: Vec<usize> = (0..n).filter(|col| !used_cols.contains(&col)).collect();
+type Galaxy = Point;
 
-    println!(
-        "Galaxies: {:?}, Vacant rows: {:?}, Vacant cols: {:?}",
-        galaxies.len(),
-        vacant_rows.len(),
-        vacant_cols.len()
+fn expand_map(map: &Grid<u8>, expansion_factor: usize) -> Result<GalacticCoordinates> {
+    let mut galaxies: Vec<Galaxy> = map
+        .iter()
+        .filter(|(_, &ch)| ch == b'#')
+        .map(|(pos, _)| pos)
+        .collect();
+
+    let vacant_rows: Vec<usize> = map
+        .rows()
+        .enumerate()
+        .filter(|(_, row)| !row.contains(&b'#'))
+        .map(|(ix, _)| ix)
+        .collect();
+    let vacant_cols: Vec<usize> = map
+        .columns()
+        .enumerate()
+        .filter_map(|(ix, mut col)| (!col.any(|&ch| ch == b'#')).then_some(ix))
+        .collect();
+
+    debug!(
+        galaxies = galaxies.len(),
+        vacant_rows = vacant_rows.len(),
+        vacant_cols = vacant_cols.len(),
+        "expanding map"
     );
 
-    for galaxy in 0..galaxies.len() {
-        let mut gal_x_exp = 0;
-        let mut gal_y_exp = 0;
+    // every vacant row or column before a galaxy pushes it `expansion_factor` further out
+    let expand = |pos: usize, vacant: &[usize]| {
+        let passed = vacant.iter().filter(|&&v| pos >= v).count();
+        passed
+            .checked_mul(expansion_factor)
+            .and_then(|growth| pos.checked_add(growth))
+            .ok_or_else(|| AocError::overflow("expanding the universe"))
+    };
+
+    for galaxy in galaxies.iter_mut() {
+        galaxy.0 = expand(galaxy.0, &vacant_rows)?;
+        galaxy.1 = expand(galaxy.1, &vacant_cols)?;
+    }
+
+    Ok(galaxies)
+}
 
-        vacant_rows.iter().for_each(|expansion_row| {
-            if galaxies[galaxy].0 >= *expansion_row {
-                gal_x_exp += expansion_factor
-            }
-        });
+pub struct Day11 {
+    image: Grid<u8>,
+}
 
-        vacant_cols.iter().for_each(|expansion_col| {
-            if galaxies[galaxy].1 >= *expansion_col {
-                gal_y_exp += expansion_factor
-            }
-        });
+impl Solution for Day11 {
+    fn parse(input: &Input) -> Result<Self> {
+        let image = input.byte_grid()?;
+        if image.height() < 2 {
+            return Err(AocError::invalid("Image must have more than one row"));
+        }
 
-        galaxies[galaxy].1 += gal_y_exp;
-        galaxies[galaxy].0 += gal_x_exp;
+        Ok(Day11 { image })
     }
 
-    galaxies
+    fn part1(&self) -> Result<Answer> {
+        Ok(solve(&self.image, 1, calculate_manhattan_distances)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(solve(&self.image, 999999, calculate_manhattan_distances)?.into())
+    }
 }
 
 #[cfg(test)]
@@ -374,9 +398,9 @@ mod tests {
     #[test]
     fn day11_part2_file() {
         let start = std::time::Instant::now();
-        let input = include_bytes!("fixtures/day11.txt");
+        let input = crate::fixture(11).unwrap().byte_grid().unwrap();
 
-        let total_min_spans = solve(input, 999999, calculate_approx_distances);
+        let total_min_spans = solve(&input, 999999, calculate_approx_distances).unwrap();
 
         assert_eq!(total_min_spans, 597714117556usize);
         println!("Process in: {:?}", start.elapsed());
@@ -397,8 +421,8 @@ mod tests {
 .......#..
 #...#....."#;
 
-        let input = input.as_bytes();
-        let total_min_spans = solve(input, 9, calculate_approx_distances);
+        let input: Grid<u8> = input.parse().unwrap();
+        let total_min_spans = solve(&input, 9, calculate_approx_distances).unwrap();
 
         assert_eq!(total_min_spans, 1030usize);
         println!("Process in: {:?}", start.elapsed());
@@ -419,8 +443,8 @@ mod tests {
 .......#..
 #...#....."#;
 
-        let input = input.as_bytes();
-        let total_min_spans = solve(input, 99, calculate_approx_distances);
+        let input: Grid<u8> = input.parse().unwrap();
+        let total_min_spans = solve(&input, 99, calculate_approx_distances).unwrap();
 
         assert_eq!(total_min_spans, 8410usize);
         println!("Process in: {:?}", start.elapsed());
@@ -429,9 +453,9 @@ mod tests {
     #[test]
     fn day11_file1() {
         let start = std::time::Instant::now();
-        let input = include_bytes!("fixtures/day11.txt");
+        let input = crate::fixture(11).unwrap().byte_grid().unwrap();
 
-        let total_min_spans = solve(input, 1, calculate_approx_distances);
+        let total_min_spans = solve(&input, 1, calculate_approx_distances).unwrap();
 
         assert_eq!(total_min_spans, 9312968usize);
         println!("Process in: {:?}", start.elapsed());
@@ -451,13 +475,44 @@ mod tests {
 .......#..
 #...#....."#;
 
-        let input = input.as_bytes();
+        let input: Grid<u8> = input.parse().unwrap();
 
-        let total_min_spans = solve(input, 1, calculate_approx_distances);
+        let total_min_spans = solve(&input, 1, calculate_approx_distances).unwrap();
 
         assert_eq!(total_min_spans, 374usize);
+
+        assert_eq!(
+            solve(&input, 1, calculate_manhattan_distances).unwrap(),
+            374usize
+        );
+        assert_eq!(
+            solve(&input, 9, calculate_manhattan_distances).unwrap(),
+            1030usize
+        );
+        assert_eq!(
+            solve(&input, 99, calculate_manhattan_distances).unwrap(),
+            8410usize
+        );
         println!("Process in: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn distance_overflow() {
+        let far = usize::MAX;
+
+        assert!(matches!(
+            calculate_manhattan_distances(vec![(0, 0), (far, far)]),
+            Err(AocError::Overflow { .. })
+        ));
+        assert!(matches!(
+            calculate_manhattan_distances(vec![(0, 0), (far, 0), (0, 1)]),
+            Err(AocError::Overflow { .. })
+        ));
+        assert_eq!(
+            calculate_manhattan_distances(vec![(0, 0), (far, 0)]).unwrap(),
+            far
+        );
+    }
 }
 
 // This only works for small matrices
@@ -99,12 +99,22 @@
 #[allow(unused)]
 #[allow(unused_variables)]
 #[allow(unused_assignments)]
-use anyhow::Result;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::hash::Hash;
 use std::sync::atomic::AtomicUsize;
 
+use tracing::{debug, trace};
+use winnow::ascii::space1;
+use winnow::combinator::separated_pair;
+use winnow::token::take_while;
+use winnow::Parser;
+
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
+
 #[inline(always)]
 fn verify_placement(
     d: usize,
@@ -240,15 +250,23 @@ fn get_count_r<'a, 'b>(
     total
 }
 
-fn get_count<'a>(line: &'a str) -> usize {
-    let records: Vec<&str> = line.split(" ").collect();
-    let (data, rl_data) = (records[0], records[1]);
+// "???.### 1,1,3": springs, then the lengths of each run of damaged springs
+fn parse_record(line: &str) -> Result<(&str, Vec<usize>)> {
+    let springs = take_while(1.., ['.', '#', '?']).context(parse::label("springs"));
+    let run_length = parse::unsigned
+        .verify(|&run: &usize| run > 0)
+        .context(parse::label("run length"));
+
+    parse::run(
+        line,
+        separated_pair(springs, space1, parse::separated_by(run_length, ',')),
+    )
+}
+
+fn get_count<'a>(line: &'a str) -> Result<usize> {
+    let (data, rl_data) = parse_record(line)?;
 
     let data = data.as_bytes();
-    let rl_data: Vec<usize> = rl_data
-        .split(",")
-        .map(|n| n.parse::<usize>().expect("Input data is numbers"))
-        .collect();
 
     let mut cache = HashMap::<(&'a str, &'a [usize]), usize>::new();
     let line_len = data.len() - 1;
@@ -263,7 +281,7 @@ fn get_count<'a>(line: &'a str) -> usize {
         0,
         &mut cache,
     );
-    s
+    Ok(s)
 }
 
 #[derive(Debug, Clone)]
@@ -283,14 +301,8 @@ enum Search<'a> {
     Add(usize),
 }
 
-fn get_count_iter<'a>(line: &'a str) -> usize {
-    let records: Vec<&str> = line.split(" ").collect();
-    let (data, rl_data) = (records[0], records[1]);
-
-    let rl_data: Vec<usize> = rl_data
-        .split(",")
-        .map(|n| n.parse::<usize>().expect("Input data is numbers"))
-        .collect();
+fn get_count_iter<'a>(line: &'a str) -> Result<usize> {
+    let (data, rl_data) = parse_record(line)?;
 
     let line = data.as_bytes();
     let line_len = line.len() - 1;
@@ -329,7 +341,7 @@ fn get_count_iter<'a>(line: &'a str) -> usize {
 
                 // we've found an arrangement when all run length indices are used/placed
                 if rl_idx > rl_len {
-                    println!("Ret 1");
+                    trace!("arrangement found");
                     stack.push(Search::Add(1));
                     continue 'top;
                 }
@@ -354,7 +366,11 @@ fn get_count_iter<'a>(line: &'a str) -> usize {
                         Search::Resume(parent_ctx) => {
                             parent_ctx.total += value;
                         }
-                        _ => panic!("should always resume after an add op"),
+                        _ => {
+                            return Err(AocError::invalid(
+                                "search should always resume after an add op",
+                            ))
+                        }
                     }
                 }
                 let len = stack.len();
@@ -440,56 +456,60 @@ fn get_count_iter<'a>(line: &'a str) -> usize {
 
         grand_total += total;
     }
-    dbg!(&cache);
+    trace!(?cache, "arrangement cache");
+
+    Ok(grand_total)
+}
 
-    grand_total
+fn sum_arrangements(counts: impl ParallelIterator<Item = Result<usize>>) -> Result<usize> {
+    counts.try_reduce(
+        || 0,
+        |a, b| {
+            a.checked_add(b)
+                .ok_or_else(|| AocError::overflow("summing arrangements"))
+        },
+    )
 }
 
 fn solve_stream_iter(all_data: &str, unfold: bool) -> Result<usize> {
-    let lines = all_data.par_lines();
-
-    let arrangements: Result<usize> = lines
-        .map(|line| {
-            let line = if unfold {
-                unfold_stream(line)?
-            } else {
-                line.to_owned()
-            };
+    let lines: Vec<&str> = all_data.lines().collect();
 
-            let cnt = get_count_iter(&line);
-            Ok(cnt)
-        })
-        .sum();
+    sum_arrangements(lines.par_iter().enumerate().map(|(ix, line)| {
+        let line = if unfold {
+            unfold_stream(line)
+        } else {
+            Ok(line.to_string())
+        };
 
-    arrangements
+        line.and_then(|line| get_count_iter(&line))
+            .map_err(|e| e.on_line(ix + 1))
+    }))
 }
 
 fn solve_stream(all_data: &str, unfold: bool) -> Result<usize> {
-    let lines = all_data.par_lines();
+    let lines: Vec<&str> = all_data.lines().collect();
     let n = AtomicUsize::new(0);
 
-    let arrangements: Result<usize> = lines
-        .map(|line| {
-            let line = if unfold {
-                unfold_stream(line)?
-            } else {
-                line.to_owned()
-            };
-
-            let mut h = std::hash::DefaultHasher::new();
-            line.as_bytes().hash(&mut h);
-            let cnt = get_count(&line);
-            let this_n = n.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-            println!("Sum for line {:?}\t\t{:?}\t\t{:?}", this_n, &cnt, &line);
+    sum_arrangements(lines.par_iter().enumerate().map(|(ix, line)| {
+        let line = if unfold {
+            unfold_stream(line)
+        } else {
+            Ok(line.to_string())
+        };
 
-            Ok(cnt)
-        })
-        .sum();
+        let line = line.map_err(|e| e.on_line(ix + 1))?;
+        let mut h = std::hash::DefaultHasher::new();
+        line.as_bytes().hash(&mut h);
+        let cnt = get_count(&line).map_err(|e| e.on_line(ix + 1))?;
+        let this_n = n.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        debug!(line_no = this_n, count = cnt, record = %line, "counted arrangements");
 
-    arrangements
+        Ok(cnt)
+    }))
 }
 
 fn unfold_stream(line: &str) -> Result<String> {
+    parse_record(line)?;
     let recs: Vec<&str> = line.split(' ').collect();
 
     let mut side1 = vec![];
@@ -512,6 +532,30 @@ fn unfold_stream(line: &str) -> Result<String> {
     Ok(side1)
 }
 
+pub struct Day12 {
+    records: String,
+}
+
+impl Solution for Day12 {
+    fn parse(input: &Input) -> Result<Self> {
+        for (ix, line) in input.lines().enumerate() {
+            parse_record(line).map_err(|e| e.on_line(ix + 1))?;
+        }
+
+        Ok(Day12 {
+            records: input.as_str().to_owned(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(solve_stream(&self.records, false)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(solve_stream(&self.records, true)?.into())
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -519,9 +563,9 @@ pub mod tests {
     #[test]
     fn day12_file2_rec() {
         let start = std::time::Instant::now();
-        let input = std::str::from_utf8(include_bytes!("fixtures/day12.txt")).expect("valid str");
+        let input = crate::fixture(12).unwrap();
 
-        let total_min_spans = solve_stream(input, true).unwrap();
+        let total_min_spans = solve_stream(input.as_str(), true).unwrap();
 
         assert_eq!(total_min_spans, 1909291258644usize);
         println!("Process in: {:?}", start.elapsed());
@@ -530,9 +574,9 @@ pub mod tests {
     #[test]
     fn day12_file2_iter() {
         let start = std::time::Instant::now();
-        let input = std::str::from_utf8(include_bytes!("fixtures/day12.txt")).expect("valid str");
+        let input = crate::fixture(12).unwrap();
 
-        let total_min_spans = solve_stream_iter(input, true).unwrap();
+        let total_min_spans = solve_stream_iter(input.as_str(), true).unwrap();
 
         assert_eq!(total_min_spans, 1909291258644usize);
         println!("Process in: {:?}", start.elapsed());
@@ -541,10 +585,9 @@ pub mod tests {
     #[test]
     fn day12_file1_iter() {
         let start = std::time::Instant::now();
-        let input = std::str::from_utf8(include_bytes!("fixtures/day12.txt")).expect("valid str");
-        // let input = include_bytes!("fixtures/day12.txt");
+        let input = crate::fixture(12).unwrap();
 
-        let total_min_spans = solve_stream_iter(input, false).unwrap();
+        let total_min_spans = solve_stream_iter(input.as_str(), false).unwrap();
 
         assert_eq!(total_min_spans, 7260usize);
         println!("Process in: {:?}", start.elapsed());
@@ -587,9 +630,9 @@ pub mod tests {
     #[test]
     fn day12_part2_file() {
         let start = std::time::Instant::now();
-        let input = std::str::from_utf8(include_bytes!("fixtures/day12.txt")).unwrap();
+        let input = crate::fixture(12).unwrap();
 
-        let result = solve_stream_iter(input, true).unwrap();
+        let result = solve_stream_iter(input.as_str(), true).unwrap();
         assert_eq!(result, 0usize);
         println!("Process in: {:?}", start.elapsed());
     }
@@ -612,9 +655,9 @@ pub mod tests {
     #[test]
     fn day12_file1() {
         let start = std::time::Instant::now();
-        let input = std::str::from_utf8(include_bytes!("fixtures/day12.txt")).expect("valid str");
+        let input = crate::fixture(12).unwrap();
 
-        let total_min_spans = solve_stream(input, false).unwrap();
+        let total_min_spans = solve_stream(input.as_str(), false).unwrap();
 
         assert_eq!(total_min_spans, 7260usize);
         println!("Process in: {:?}", start.elapsed());
@@ -624,32 +667,32 @@ pub mod tests {
     fn day12_tiny1() {
         println!("Test1");
         let input = "?###???????? 3,2,1";
-        let result = get_count(input);
+        let result = get_count(input).unwrap();
         assert_eq!(result, 10usize);
 
         println!("Test2");
         let input = "????.######..#####. 1,6,5";
-        let result = get_count(input);
+        let result = get_count(input).unwrap();
         assert_eq!(result, 4usize);
 
         println!("Test3");
         let input = "????.#...#... 4,1,1";
-        let result = get_count(input);
+        let result = get_count(input).unwrap();
         assert_eq!(result, 1usize);
 
         println!("Test4");
         let input = "?#?#?#?#?#?#?#? 1,3,1,6";
-        let result = get_count(input);
+        let result = get_count(input).unwrap();
         assert_eq!(result, 1usize);
 
         println!("Test5");
         let input = ".??..??...?##. 1,1,3";
-        let result = get_count(input);
+        let result = get_count(input).unwrap();
         assert_eq!(result, 4usize);
 
         println!("Test6");
         let input = "???.### 1,1,3";
-        let result = get_count(input);
+        let result = get_count(input).unwrap();
         assert_eq!(result, 1usize);
     }
 
@@ -657,7 +700,7 @@ pub mod tests {
     fn day12_tiny2() {
         println!("Test7");
         let input = "?.???.?#.????#?#??# 1,1,1,1,8";
-        let result = get_count_iter(input);
+        let result = get_count_iter(input).unwrap();
         assert_eq!(result, 5usize);
 
         println!();
@@ -666,7 +709,7 @@ pub mod tests {
 
         println!("Test7_rec");
         let input = "?.???.?#.????#?#??# 1,1,1,1,8";
-        let result = get_count(input);
+        let result = get_count(input).unwrap();
         assert_eq!(result, 5usize);
     }
 
@@ -681,4 +724,31 @@ pub mod tests {
         assert_eq!(total_min_spans, 10usize);
         println!("Process in: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn day12_invalid_records() {
+        let err = solve_stream("???.### 1,1,3\n.??..??...?##. 1,x,3\n", false).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 18,
+                ..
+            }
+        ));
+
+        let err = get_count("?#?X 1").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 1,
+                column: 4,
+                ..
+            }
+        ));
+
+        assert!(get_count("???").is_err());
+        assert!(get_count_iter("??? 0").is_err());
+        assert!(unfold_stream(" 1").is_err());
+    }
 }
@@ -112,97 +112,81 @@
 
 use std::hash::{DefaultHasher, Hash, Hasher};
 
+use winnow::ascii::multispace0;
+use winnow::combinator::terminated;
+
+use aoc_core::error::{AocError, Result};
+use aoc_core::grid::Grid;
+use aoc_core::input::Input;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
+
 #[derive(Eq, PartialEq, Clone, Debug)]
 enum MirrorOrientation {
     Horz,
     Vert,
 }
-type MirrorDetail = (MirrorOrientation, usize);
 
-fn solve_smudges(input: &str) -> usize {
-    let blocks: Vec<&str> = input.split("\n\n").collect();
-    let blocks: Vec<Vec<&str>> = blocks.iter().map(|block| block.lines().collect()).collect();
+type Pattern = Grid<char>;
+
+fn parse_patterns(input: &Input) -> Result<Vec<Pattern>> {
+    parse::run(
+        input.as_str(),
+        terminated(parse::blocks(parse::char_grid), multispace0),
+    )
+}
+
+fn no_reflection(ix: usize) -> AocError {
+    AocError::invalid(format!("Pattern {} has no line of reflection", ix + 1))
+}
+
+fn solve_smudges(patterns: &[Pattern]) -> Result<usize> {
     let mut sum = 0usize;
 
-    let flip = |test: char| -> &str {
+    let flip = |test: char| -> char {
         if test == '#' {
-            return ".";
+            return '.';
         }
-        return "#";
+        '#'
     };
 
-    'block_iter: for block in blocks.iter() {
-        let (max_row, max_col) = (block.len().clone(), block[0].len().clone());
+    'block_iter: for (ix, block) in patterns.iter().enumerate() {
         let def_horz_hashes = make_horz_hash_grids(block);
         let def_vert_hashes = make_vert_hash_grids(block);
-        let selected_def = select_mirror_block(&def_horz_hashes, &def_vert_hashes, &None);
-
-        for ir in 0..max_row {
-            for ic in 0..max_col {
-                let test_position: Vec<char> = block[ir]
-                    .char_indices()
-                    .filter(|(i, _)| i == &ic)
-                    .map(|c| c.1)
-                    .take(1)
-                    .collect();
-                debug_assert!(test_position.len() == 1);
-
-                let flipped_position = flip(test_position[0]);
-                let try_line = format!(
-                    "{}{}{}",
-                    block[ir][0..ic].to_owned(),
-                    flipped_position,
-                    block[ir][ic + 1..].to_owned()
-                );
-
-                let try_block = {
-                    let (first, last) = block.split_at(ir);
-                    let mut v = Vec::with_capacity(max_row);
-                    v.extend(
-                        first
-                            .iter()
-                            .map(|s| (*s).to_owned())
-                            .collect::<Vec<String>>(),
-                    );
-                    v.push(try_line.clone());
-                    v.extend(
-                        last[1..]
-                            .iter()
-                            .map(|s| (*s).to_owned())
-                            .collect::<Vec<String>>(),
-                    );
-                    v
-                };
-
-                let ref_block = try_block.iter().map(|l| &l[..]).collect();
-                let horz_hash_try = make_horz_hash_grids(&ref_block);
-                let vert_hash_try: Vec<u64> = make_vert_hash_grids(&ref_block);
-
-                let try_mirror_block: Option<(MirrorOrientation, usize)> =
-                    select_mirror_block(&horz_hash_try, &vert_hash_try, &selected_def);
-
-                if let Some(try_mirror) = try_mirror_block.clone() {
-                    let selected_def = selected_def.clone().unwrap();
-                    if try_mirror != selected_def {
-                        sum += try_mirror.1;
-                        continue 'block_iter;
-                    }
+        let selected_def = select_mirror_block(&def_horz_hashes, &def_vert_hashes, &None)
+            .ok_or_else(|| no_reflection(ix))?;
+        let skip = Some(selected_def.clone());
+
+        for (position, &tile) in block.iter() {
+            let mut try_block = block.clone();
+            try_block[position] = flip(tile);
+
+            let horz_hash_try = make_horz_hash_grids(&try_block);
+            let vert_hash_try: Vec<u64> = make_vert_hash_grids(&try_block);
+
+            let try_mirror_block: Option<(MirrorOrientation, usize)> =
+                select_mirror_block(&horz_hash_try, &vert_hash_try, &skip);
+
+            if let Some(try_mirror) = try_mirror_block {
+                if try_mirror != selected_def {
+                    sum += try_mirror.1;
+                    continue 'block_iter;
                 }
             }
         }
 
-        let (_, selected_def_idx) = selected_def.unwrap();
+        let (_, selected_def_idx) = selected_def;
         sum += selected_def_idx;
     }
 
-    sum
+    Ok(sum)
 }
 
 #[inline(always)]
-fn make_horz_hash_grids(lines: &Vec<&str>) -> Vec<u64> {
-    lines
-        .iter()
-        .map(|&line| {
+fn make_horz_hash_grids(block: &Pattern) -> Vec<u64> {
+    block
+        .rows()
+        .map(|line| {
             let mut hasher = DefaultHasher::new();
             line.hash(&mut hasher);
             hasher.finish()
@@ -211,76 +195,19 @@ fn make_horz_hash_grids(lines: &Vec<&str>) -> Vec<u64> {
 }
 
 #[inline(always)]
-fn make_vert_hash_grids(lines: &Vec<&str>) -> Vec<u64> {
-    let mut column_hashes: Vec<DefaultHasher> = vec![DefaultHasher::new(); lines[0].len()];
-
-    lines.iter().for_each(|&line| {
-        for (ix, c) in line.chars().enumerate() {
-            c.hash(&mut column_hashes[ix]);
-        }
-    });
-
-    let ver_hashes: Vec<u64> = column_hashes
-        .iter()
-        .map(|h: &DefaultHasher| h.finish())
-        .collect();
-
-    ver_hashes
-}
-
-#[inline(always)]
-fn make_horz_hash_grids_from_lines(input: &str) -> Vec<Vec<u64>> {
-    let mut all_hor_blocks = vec![];
-    let mut line_block = vec![];
-
-    for l in input.lines() {
-        if l.trim().len() > 0 {
+fn make_vert_hash_grids(block: &Pattern) -> Vec<u64> {
+    block
+        .columns()
+        .map(|column| {
             let mut hasher = DefaultHasher::new();
-            l.hash(&mut hasher);
-
-            line_block.push(hasher.finish());
-        } else {
-            all_hor_blocks.push(line_block);
-            line_block = vec![];
-        }
-    }
-    all_hor_blocks.push(line_block);
-    all_hor_blocks
-}
-
-#[inline(always)]
-fn make_vert_hash_grids_from_lines(input: &str) -> Vec<Vec<u64>> {
-    let mut vertical_hashers: Vec<Vec<DefaultHasher>> = vec![];
-    let mut init_block = true;
-    let mut vert_block_id = 0usize;
-    for line in input.lines() {
-        if init_block {
-            vertical_hashers.push(vec![]);
-            for _ in 0..line.len() {
-                vertical_hashers[vert_block_id].push(DefaultHasher::new());
-            }
-        }
-        init_block = false;
-
-        if line.trim().len() > 0 {
-            for (ix, c) in line.chars().enumerate() {
-                c.hash(&mut vertical_hashers[vert_block_id][ix]);
-            }
-        } else {
-            vert_block_id += 1;
-            init_block = true;
-        }
-    }
-    let all_ver_blocks: Vec<Vec<u64>> = vertical_hashers
-        .iter()
-        .map(|v| v.iter().map(|h| h.finish()).collect())
-        .collect();
-
-    all_ver_blocks
+            column.for_each(|c| c.hash(&mut hasher));
+            hasher.finish()
+        })
+        .collect()
 }
 
 #[inline(always)]
-fn ident_mirror_and_idx(block: &Vec<u64>, skip_idx: Option<usize>) -> Option<usize> {
+fn ident_mirror_and_idx(block: &[u64], skip_idx: Option<usize>) -> Option<usize> {
     let mut mirror_stack: Vec<u64> = vec![];
     let mut prev_line: Option<u64> = None;
     let mut mirror_check = false;
@@ -289,8 +216,7 @@ fn ident_mirror_and_idx(block: &Vec<u64>, skip_idx: Option<usize>) -> Option<usi
 
     for (ix, curr_line) in block.iter().enumerate() {
         if !mirror_check {
-            if prev_line
-                .is_some_and(|p| p == *curr_line && !skip_idx.is_some_and(|skip| skip == ix))
+            if prev_line.is_some_and(|p| p == *curr_line && skip_idx.is_none_or(|skip| skip != ix))
             {
                 mirror_idx = Some(ix);
                 if ix > 1 {
@@ -300,31 +226,27 @@ fn ident_mirror_and_idx(block: &Vec<u64>, skip_idx: Option<usize>) -> Option<usi
                 }
                 mirror_check = true;
             }
-            mirror_stack.push(curr_line.clone());
-            prev_line = Some(curr_line.clone());
+            mirror_stack.push(*curr_line);
+            prev_line = Some(*curr_line);
         } else {
             if let Some(cidx) = check_idx {
                 if mirror_stack.get(cidx).is_some_and(|p| p == curr_line) {
-                    if check_idx.is_some_and(|c| c > 0) {
-                        check_idx = Some(check_idx.expect("valid") - 1);
-                    } else {
-                        check_idx = None;
-                    }
+                    check_idx = cidx.checked_sub(1);
                 } else {
                     mirror_check = false;
                     mirror_idx = None;
                 }
             }
-            mirror_stack.push(curr_line.clone());
-            prev_line = Some(curr_line.clone());
+            mirror_stack.push(*curr_line);
+            prev_line = Some(*curr_line);
         }
     }
     mirror_idx
 }
 
 fn select_mirror_block(
-    horz_block: &Vec<u64>,
-    vert_block: &Vec<u64>,
+    horz_block: &[u64],
+    vert_block: &[u64],
     skip_idx: &Option<(MirrorOrientation, usize)>,
 ) -> Option<(MirrorOrientation, usize)> {
     let h_skip = skip_idx
@@ -333,7 +255,7 @@ fn select_mirror_block(
         .map(|(_, skip_idx)| skip_idx / 100);
 
     let h_mirror_idx =
-        ident_mirror_and_idx(&horz_block, h_skip).map(|h| (MirrorOrientation::Horz, h * 100));
+        ident_mirror_and_idx(horz_block, h_skip).map(|h| (MirrorOrientation::Horz, h * 100));
 
     let v_skip = skip_idx
         .clone()
@@ -341,43 +263,61 @@ fn select_mirror_block(
         .map(|(_, skip_idx)| skip_idx);
 
     let v_mirror_idx =
-        ident_mirror_and_idx(&vert_block, v_skip).map(|v| (MirrorOrientation::Vert, v));
+        ident_mirror_and_idx(vert_block, v_skip).map(|v| (MirrorOrientation::Vert, v));
 
     h_mirror_idx.or(v_mirror_idx)
 }
 
-fn select_mirror_blocks(
-    hor_blocks: Vec<Vec<u64>>,
-    ver_blocks: Vec<Vec<u64>>,
-) -> impl Iterator<Item = Option<(MirrorOrientation, usize)>> {
-    hor_blocks
-        .into_iter()
-        .zip(ver_blocks)
-        .map(|(horz_block, vert_block)| select_mirror_block(&horz_block, &vert_block, &None))
+fn solve(patterns: &[Pattern]) -> Result<usize> {
+    patterns
+        .iter()
+        .enumerate()
+        .map(|(ix, block)| {
+            select_mirror_block(
+                &make_horz_hash_grids(block),
+                &make_vert_hash_grids(block),
+                &None,
+            )
+            .map(|(_, idx)| idx)
+            .ok_or_else(|| no_reflection(ix))
+        })
+        .sum()
 }
 
-fn solve(input: &str) -> usize {
-    let all_hor_blocks = make_horz_hash_grids_from_lines(input);
-    let all_ver_blocks: Vec<Vec<u64>> = make_vert_hash_grids_from_lines(input);
+pub struct Day13 {
+    patterns: Vec<Pattern>,
+}
+
+impl Solution for Day13 {
+    fn parse(input: &Input) -> Result<Self> {
+        Ok(Day13 {
+            patterns: parse_patterns(input)?,
+        })
+    }
 
-    let mirror_blocks: Vec<usize> = select_mirror_blocks(all_hor_blocks, all_ver_blocks)
-        .filter(|b| b.is_some())
-        .map(|b| b.unwrap())
-        .map(|(_, idx)| idx)
-        .collect();
-    mirror_blocks.iter().sum()
+    fn part1(&self) -> Result<Answer> {
+        Ok(solve(&self.patterns)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(solve_smudges(&self.patterns)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn patterns(input: &str) -> Vec<Pattern> {
+        parse_patterns(&Input::new(input)).unwrap()
+    }
+
     #[test]
     fn day13_part2_file() {
         let start = std::time::Instant::now();
 
-        let input = std::str::from_utf8(include_bytes!("fixtures/day13.txt")).expect("valid str");
-        let result = solve_smudges(input);
+        let input = crate::fixture(13).unwrap();
+        let result = solve_smudges(&parse_patterns(&input).unwrap()).unwrap();
 
         assert_eq!(result, 31974);
         println!("Process in: {:?}", start.elapsed());
@@ -392,7 +332,7 @@ mod tests {
 #..#.#.##.#.#
 ....##.##.##.
 .....#....#.."#;
-        let result = solve_smudges(input);
+        let result = solve_smudges(&patterns(input)).unwrap();
         assert_eq!(result, 8);
     }
 
@@ -405,7 +345,7 @@ mod tests {
 #...#..#.
 ..####.##
 .###....#"#;
-        let result = solve_smudges(input);
+        let result = solve_smudges(&patterns(input)).unwrap();
         assert_eq!(result, 6);
     }
 
@@ -426,7 +366,7 @@ mod tests {
 #####.##.
 ..##..###
 #....#..#"#;
-        let result = solve_smudges(input);
+        let result = solve_smudges(&patterns(input)).unwrap();
         assert_eq!(result, 400);
     }
 
@@ -434,8 +374,8 @@ mod tests {
     fn day13_part1_file() {
         let start = std::time::Instant::now();
 
-        let input = std::str::from_utf8(include_bytes!("fixtures/day13.txt")).expect("valid str");
-        let result = solve(input);
+        let input = crate::fixture(13).unwrap();
+        let result = solve(&parse_patterns(&input).unwrap()).unwrap();
 
         assert_eq!(result, 35210);
         println!("Process in: {:?}", start.elapsed());
@@ -452,7 +392,7 @@ mod tests {
 ..###...####.
 #####....####"#;
 
-        let result = solve(input);
+        let result = solve(&patterns(input)).unwrap();
         assert_eq!(result, 1);
     }
 
@@ -476,7 +416,7 @@ mod tests {
 ..####.##...#.#
 ...#.#.###.###."#;
 
-        let result = solve(input);
+        let result = solve(&patterns(input)).unwrap();
         assert_eq!(result, 1300);
     }
 
@@ -490,7 +430,7 @@ mod tests {
 ##..#.###..
 ##..#.###.."#;
 
-        let result = solve(input);
+        let result = solve(&patterns(input)).unwrap();
         assert_eq!(result, 600);
     }
 
@@ -511,7 +451,17 @@ mod tests {
 #####.##.
 ..##..###
 #....#..#"#;
-        let result = solve(input);
+        let result = solve(&patterns(input)).unwrap();
         assert_eq!(result, 405);
     }
+
+    #[test]
+    fn day13_invalid_patterns() {
+        let err = parse_patterns(&Input::new("#.\n.#\n\n##\n#\n")).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 5, .. }));
+
+        let err = solve(&patterns("#.\n.#\n\n#.#\n.#.\n")).unwrap_err();
+        assert!(matches!(err, AocError::InvalidState { .. }));
+        assert!(err.to_string().contains("Pattern 1"));
+    }
 }
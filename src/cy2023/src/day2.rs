@@ -1,144 +1,1371 @@
 #![allow(dead_code)]
-use std::collections::BinaryHeap;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 
-const RED_MAX: usize = 12;
-const GREEN_MAX: usize = 13;
-const BLUE_MAX: usize = 14;
+use winnow::ascii::{space0, space1};
+use winnow::combinator::{preceded, separated_pair};
+use winnow::token::take_while;
+use winnow::{PResult, Parser};
 
-#[derive(Debug)]
-struct GameStatistic {
-    id: usize,
-    red: BinaryHeap<usize>,
-    green: BinaryHeap<usize>,
-    blue: BinaryHeap<usize>,
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
+
+/*/ How many cubes of each colour the bag holds. A colour the limits don't
+mention isn't in the bag at all, so any game drawing it is impossible.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BagLimits {
+    limits: BTreeMap<String, usize>,
 }
 
-impl GameStatistic {
-    fn new(id: usize) -> Self {
-        GameStatistic {
-            id,
-            red: BinaryHeap::new(),
-            green: BinaryHeap::new(),
-            blue: BinaryHeap::new(),
+impl BagLimits {
+    pub fn new<K: Into<String>>(limits: impl IntoIterator<Item = (K, usize)>) -> Result<Self> {
+        let mut bag = BagLimits {
+            limits: BTreeMap::new(),
+        };
+        for (colour, count) in limits {
+            let colour = colour.into();
+            if colour.is_empty() {
+                return Err(AocError::invalid("cube colours cannot be empty"));
+            }
+            if bag.limits.insert(colour.clone(), count).is_some() {
+                return Err(AocError::invalid(format!("bag limits name {colour} twice")));
+            }
         }
+
+        Ok(bag)
+    }
+
+    // the bag from the puzzle: 12 red, 13 green and 14 blue cubes
+    pub fn standard() -> Self {
+        BagLimits::new([("red", 12), ("green", 13), ("blue", 14)])
+            .expect("the standard bag is valid")
+    }
+
+    pub fn limit(&self, colour: &str) -> usize {
+        self.limits.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        self.limits.keys().map(String::as_str)
+    }
+
+    // whether a game needing `maxima` cubes of each colour could be played
+    pub fn allows(&self, maxima: &BTreeMap<String, usize>) -> bool {
+        maxima
+            .iter()
+            .all(|(colour, &needed)| needed <= self.limit(colour))
     }
 }
 
-type AggregatorFn =
-    fn(red_max: usize, green_max: usize, blue_max: usize, acc: usize, id: usize) -> usize;
+impl Default for BagLimits {
+    fn default() -> Self {
+        BagLimits::standard()
+    }
+}
 
-pub fn parse_file(file_content: String, aggregator_fn: AggregatorFn) -> usize {
-    let game_stats = file_content.lines().map(|game_line| {
-        let mut first_split = game_line.split(':');
-        let mut gs = GameStatistic::new(
-            first_split
-                .next()
-                .unwrap()
-                .split(' ')
-                .skip(1)
-                .collect::<String>()
-                .parse()
-                .expect("Must have game ID first"),
-        );
+// "12 red, 13 green, 14 blue"
+impl FromStr for BagLimits {
+    type Err = AocError;
 
-        first_split
-            .next()
-            .unwrap()
-            .split(';')
-            .for_each(|all_bags_for_game| {
-                all_bags_for_game.split(',').for_each(|d| {
-                    let num_cubes_and_color = d.trim().split(' ').collect::<Vec<&str>>();
-                    let cube_num: usize = num_cubes_and_color[0]
-                        .parse::<usize>()
-                        .expect("Number of cubes should be a number");
-                    let cube_color = num_cubes_and_color[1];
-
-                    match cube_color {
-                        "blue" => gs.blue.push(cube_num),
-                        "red" => gs.red.push(cube_num),
-                        "green" => gs.green.push(cube_num),
-                        _ => panic!("not gonna happen"),
-                    }
-                });
-            });
-
-        gs
-    });
-
-    let games_aggregate = game_stats.fold(
-        0,
-        |acc,
-         GameStatistic {
-             id,
-             mut red,
-             mut green,
-             mut blue,
-         }| {
-            let red_max = red.pop().expect("Must have a red");
-            let green_max = green.pop().expect("Must have a green");
-            let blue_max = blue.pop().expect("Must have a blue");
-
-            aggregator_fn(red_max, green_max, blue_max, acc, id)
-        },
-    );
-
-    games_aggregate
+    fn from_str(s: &str) -> Result<Self> {
+        let cubes = parse::run(s.trim(), parse::separated_by(cubes, ','))?;
+        BagLimits::new(cubes.into_iter().map(|(count, colour)| (colour, count)))
+    }
+}
+
+// one handful of cubes, with how many of each colour it held
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Draw {
+    cubes: BTreeMap<String, usize>,
+}
+
+impl Draw {
+    pub fn new<K: Into<String>>(cubes: impl IntoIterator<Item = (K, usize)>) -> Result<Self> {
+        let mut draw = Draw::default();
+        for (colour, count) in cubes {
+            // "3 red, 2 red" is a draw of five red cubes
+            let drawn = draw.cubes.entry(colour.into()).or_default();
+            *drawn = drawn
+                .checked_add(count)
+                .ok_or_else(|| AocError::overflow("cubes of one colour in a draw"))?;
+        }
+        Ok(draw)
+    }
+
+    pub fn count(&self, colour: &str) -> usize {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn cubes(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.cubes
+            .iter()
+            .map(|(colour, &count)| (colour.as_str(), count))
+    }
+}
+
+// the first colour a draw has more of than the bag holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub game: usize,
+    pub draw: usize,
+    pub colour: String,
+    pub drawn: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: usize,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    // the most cubes of each `palette` colour drawn at once, 0 for colours
+    // the game never drew
+    pub fn maxima<'p>(
+        &self,
+        palette: impl IntoIterator<Item = &'p str>,
+    ) -> BTreeMap<String, usize> {
+        palette
+            .into_iter()
+            .map(|colour| {
+                let drawn = self.draws.iter().map(|d| d.count(colour)).max();
+                (colour.to_owned(), drawn.unwrap_or(0))
+            })
+            .collect()
+    }
+
+    // the smallest bag the game could have been played with
+    pub fn minimal_bag(&self) -> BagLimits {
+        BagLimits {
+            limits: self.maxima(self.colours()),
+        }
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        let mut colours: Vec<&str> = self
+            .draws
+            .iter()
+            .flat_map(|d| d.cubes.keys().map(String::as_str))
+            .collect();
+        colours.sort_unstable();
+        colours.dedup();
+        colours.into_iter()
+    }
+
+    // the first draw, by position in the game, that `bag` couldn't supply
+    pub fn violation(&self, bag: &BagLimits) -> Option<Violation> {
+        self.draws.iter().enumerate().find_map(|(ix, draw)| {
+            let (colour, drawn) = draw.cubes().find(|&(c, n)| n > bag.limit(c))?;
+            Some(Violation {
+                game: self.id,
+                draw: ix,
+                colour: colour.to_owned(),
+                drawn,
+                limit: bag.limit(colour),
+            })
+        })
+    }
+
+    pub fn is_possible(&self, bag: &BagLimits) -> bool {
+        self.violation(bag).is_none()
+    }
+
+    // product of the minimal bag over `palette`, so a colour the game never
+    // drew makes it 0
+    pub fn power<'p>(&self, palette: impl IntoIterator<Item = &'p str>) -> Result<usize> {
+        self.maxima(palette)
+            .values()
+            .try_fold(1usize, |power, &count| power.checked_mul(count))
+            .ok_or_else(|| AocError::overflow(format!("power of game {}", self.id)))
+    }
+
+    // cubes of each colour drawn across the whole game
+    pub fn totals(&self) -> Result<BTreeMap<String, usize>> {
+        totals(&self.draws)
+    }
+
+    // cubes of each colour per draw
+    pub fn averages(&self) -> Result<BTreeMap<String, f64>> {
+        averages(&self.draws)
+    }
+}
+
+fn totals<'d>(draws: impl IntoIterator<Item = &'d Draw>) -> Result<BTreeMap<String, usize>> {
+    let mut totals: BTreeMap<String, usize> = BTreeMap::new();
+    for (colour, count) in draws.into_iter().flat_map(Draw::cubes) {
+        let total = totals.entry(colour.to_owned()).or_default();
+        *total = total
+            .checked_add(count)
+            .ok_or_else(|| AocError::overflow(format!("total {colour} cubes")))?;
+    }
+    Ok(totals)
+}
+
+fn averages<'d>(
+    draws: impl IntoIterator<Item = &'d Draw> + Clone,
+) -> Result<BTreeMap<String, f64>> {
+    let draw_count = draws.clone().into_iter().count() as f64;
+    Ok(totals(draws)?
+        .into_iter()
+        .map(|(colour, total)| (colour, total as f64 / draw_count))
+        .collect())
+}
+
+/*/ Every game of a record, in the order they were played, along with queries
+over them. Bags are compared on every colour either the bag or a game knows
+about, so a colour one game never drew still counts for it as none at all.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Games {
+    games: Vec<Game>,
+}
+
+impl Games {
+    pub fn new(games: Vec<Game>) -> Self {
+        Games { games }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Game> {
+        self.games.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Game> {
+        self.games.iter().find(|game| game.id == id)
+    }
+
+    // every colour in `bag` or drawn in any game, in order
+    pub fn palette<'a>(&'a self, bag: &'a BagLimits) -> Vec<&'a str> {
+        let mut palette = self.colours();
+        palette.extend(bag.colours());
+        palette.sort_unstable();
+        palette.dedup();
+        palette
+    }
+
+    pub fn feasible<'a>(&'a self, bag: &'a BagLimits) -> impl Iterator<Item = &'a Game> {
+        self.games.iter().filter(|game| game.is_possible(bag))
+    }
+
+    pub fn violations<'a>(&'a self, bag: &'a BagLimits) -> impl Iterator<Item = Violation> + 'a {
+        self.games.iter().filter_map(|game| game.violation(bag))
+    }
+
+    pub fn minimal_bags(&self) -> impl Iterator<Item = (usize, BagLimits)> + '_ {
+        self.games.iter().map(|game| (game.id, game.minimal_bag()))
+    }
+
+    pub fn sum_possible_ids(&self, bag: &BagLimits) -> Result<usize> {
+        self.feasible(bag)
+            .try_fold(0usize, |acc, game| acc.checked_add(game.id))
+            .ok_or_else(|| AocError::overflow("sum of game IDs"))
+    }
+
+    // the powers of every game's minimal bag over the palette with `bag`
+    pub fn sum_powers(&self, bag: &BagLimits) -> Result<usize> {
+        let palette = self.palette(bag);
+        self.games.iter().try_fold(0usize, |acc, game| {
+            acc.checked_add(game.power(palette.iter().copied())?)
+                .ok_or_else(|| AocError::overflow("sum of powers"))
+        })
+    }
+
+    pub fn totals(&self) -> Result<BTreeMap<String, usize>> {
+        totals(self.games.iter().flat_map(|game| &game.draws))
+    }
+
+    // cubes of each colour per draw, over every draw of every game
+    pub fn averages(&self) -> Result<BTreeMap<String, f64>> {
+        averages(self.games.iter().flat_map(|game| &game.draws))
+    }
+}
+
+/*/ The bags that agree with games labelled possible or impossible. A bag
+has to hold at least `lower`, the most of each colour any possible game
+drew, and must not be able to supply any of `excluded`, the maxima of the
+impossible games. Holding fewer cubes never makes a game possible, so `lower`
+itself is the smallest bag when there is one, and the bags form a single
+block above it. Only the colours the games drew are constrained.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BagInference {
+    lower: BTreeMap<String, usize>,
+    excluded: Vec<BTreeMap<String, usize>>,
+}
+
+// whether `bag` holds at least `needed` of every colour
+fn supplies(bag: &BTreeMap<String, usize>, needed: &BTreeMap<String, usize>) -> bool {
+    needed
+        .iter()
+        .all(|(colour, &count)| count <= bag.get(colour).copied().unwrap_or(0))
+}
+
+impl BagInference {
+    // nothing when no bag fits the labels
+    fn new(palette: &[&str], labelled: &[(&Game, bool)]) -> Option<Self> {
+        let mut lower: BTreeMap<String, usize> =
+            palette.iter().map(|&c| (c.to_owned(), 0)).collect();
+        let mut excluded = vec![];
+
+        for &(game, possible) in labelled {
+            let maxima = game.maxima(palette.iter().copied());
+            if possible {
+                for (colour, count) in maxima {
+                    let least = lower.entry(colour).or_default();
+                    *least = (*least).max(count);
+                }
+            } else {
+                excluded.push(maxima);
+            }
+        }
+
+        excluded.sort_unstable();
+        excluded.dedup();
+        if excluded.iter().any(|maxima| supplies(&lower, maxima)) {
+            return None;
+        }
+
+        Some(BagInference { lower, excluded })
+    }
+
+    pub fn smallest(&self) -> BagLimits {
+        BagLimits {
+            limits: self.lower.clone(),
+        }
+    }
+
+    pub fn contains(&self, bag: &BagLimits) -> bool {
+        supplies(&bag.limits, &self.lower)
+            && !self
+                .excluded
+                .iter()
+                .any(|maxima| supplies(&bag.limits, maxima))
+    }
+
+    // the most cubes of `colour` any of the bags holds, nothing when there is
+    // no limit. Adding cubes of one colour to the smallest bag is the way to
+    // get the most of it, so only impossible games the smallest bag nearly
+    // supplies bound it.
+    pub fn upper_bound(&self, colour: &str) -> Option<usize> {
+        self.excluded
+            .iter()
+            .filter(|maxima| {
+                maxima
+                    .iter()
+                    .all(|(c, &count)| c == colour || count <= self.lower[c])
+            })
+            .filter_map(|maxima| maxima.get(colour))
+            .map(|&count| count - 1)
+            .min()
+    }
+
+    // whether the smallest bag is the only one
+    pub fn is_unique(&self) -> bool {
+        self.lower
+            .iter()
+            .all(|(colour, &least)| self.upper_bound(colour) == Some(least))
+    }
+
+    // every bag, smallest first in each colour, or nothing when some colour
+    // has no limit
+    pub fn bags(&self) -> Option<impl Iterator<Item = BagLimits> + '_> {
+        let ranges: Vec<(&String, usize, usize)> = self
+            .lower
+            .iter()
+            .map(|(colour, &least)| Some((colour, least, self.upper_bound(colour)? - least + 1)))
+            .collect::<Option<_>>()?;
+        let size = ranges
+            .iter()
+            .try_fold(1usize, |size, &(_, _, span)| size.checked_mul(span))?;
+
+        let bags = (0..size).filter_map(move |mut ix| {
+            // `ix` read as a number whose digits are offsets into each range
+            let limits = ranges
+                .iter()
+                .rev()
+                .map(|&(colour, least, span)| {
+                    let count = least + ix % span;
+                    ix /= span;
+                    (colour.clone(), count)
+                })
+                .collect();
+            let bag = BagLimits { limits };
+            self.contains(&bag).then_some(bag)
+        });
+
+        Some(bags)
+    }
+}
+
+impl Games {
+    // every colour drawn in any game, in order
+    fn colours(&self) -> Vec<&str> {
+        let mut colours: Vec<&str> = self.games.iter().flat_map(|game| game.colours()).collect();
+        colours.sort_unstable();
+        colours.dedup();
+        colours
+    }
+
+    // the bags that make games possible or impossible as labelled by ID, games
+    // without a label can go either way
+    pub fn infer_bag(
+        &self,
+        labels: impl IntoIterator<Item = (usize, bool)>,
+    ) -> Result<Option<BagInference>> {
+        let labelled = labels
+            .into_iter()
+            .map(|(id, possible)| {
+                let game = self
+                    .get(id)
+                    .ok_or_else(|| AocError::invalid(format!("no game {id} to label")))?;
+                Ok((game, possible))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(BagInference::new(&self.colours(), &labelled))
+    }
+
+    /*/ The bags for which the possible games' IDs add up to `target`, one
+    inference for each way of splitting the games into possible and impossible.
+    Which games a bag allows only changes where a colour's count reaches the
+    maximum some game drew of it, so trying every combination of those counts
+    finds every split.
+    */
+    pub fn infer_from_sum(&self, target: usize) -> Vec<BagInference> {
+        let palette = self.colours();
+        let maxima: Vec<BTreeMap<String, usize>> = self
+            .games
+            .iter()
+            .map(|game| game.maxima(palette.iter().copied()))
+            .collect();
+        let thresholds: Vec<Vec<usize>> = palette
+            .iter()
+            .map(|&colour| {
+                let mut counts: Vec<usize> = maxima.iter().map(|m| m[colour]).chain([0]).collect();
+                counts.sort_unstable();
+                counts.dedup();
+                counts
+            })
+            .collect();
+
+        let mut inferences: Vec<BagInference> = vec![];
+        let mut choice = vec![0; palette.len()];
+        loop {
+            let bag: BTreeMap<String, usize> = palette
+                .iter()
+                .zip(&choice)
+                .zip(&thresholds)
+                .map(|((&colour, &ix), counts)| (colour.to_owned(), counts[ix]))
+                .collect();
+            let labelled: Vec<(&Game, bool)> = self
+                .games
+                .iter()
+                .zip(&maxima)
+                .map(|(game, needed)| (game, supplies(&bag, needed)))
+                .collect();
+            let sum = labelled
+                .iter()
+                .filter(|(_, possible)| *possible)
+                .try_fold(0usize, |sum, (game, _)| sum.checked_add(game.id));
+
+            if sum == Some(target) {
+                let inference = BagInference::new(&palette, &labelled)
+                    .expect("the bag that labelled the games fits them");
+                if !inferences.contains(&inference) {
+                    inferences.push(inference);
+                }
+            }
+
+            // next combination, odometer style
+            let Some(place) = (0..choice.len()).find(|&i| choice[i] + 1 < thresholds[i].len())
+            else {
+                break;
+            };
+            choice[place] += 1;
+            choice[..place].fill(0);
+        }
+
+        inferences
+    }
+}
+
+impl FromStr for Games {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let games = parse::run_lines(s, game)?
+            .into_iter()
+            .map(|(id, draws)| {
+                let draws = draws
+                    .into_iter()
+                    .map(|draw| Draw::new(draw.into_iter().map(|(count, colour)| (colour, count))))
+                    .collect::<Result<_>>()?;
+                Ok(Game { id, draws })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Games::new(games))
+    }
+}
+
+// SplitMix64, enough randomness for test inputs and the same from a seed
+// on every platform
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform enough in `range`, which must not be empty
+    fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let span = (range.end() - range.start()) as u64;
+        match span.checked_add(1) {
+            Some(span) => range.start() + (self.next() % span) as usize,
+            // every value a u64 can hold
+            None => self.next() as usize,
+        }
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i));
+        }
+    }
+}
+
+// what happens to the cubes of one draw before the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Replacement {
+    // put back in the bag, as in the puzzle
+    With,
+    // kept out, so a game never draws more of a colour than the bag holds
+    Without,
+}
+
+// how many draws each generated game has
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrawDistribution {
+    // any count in the range, evenly
+    Uniform(RangeInclusive<usize>),
+    // each count as often as its weight says, relative to the others
+    Weighted(Vec<(usize, u32)>),
+}
+
+impl DrawDistribution {
+    // the fewest and most draws a game can have, None if it can have none
+    fn bounds(&self) -> Option<(usize, usize)> {
+        match self {
+            DrawDistribution::Uniform(range) => {
+                (!range.is_empty()).then(|| (*range.start(), *range.end()))
+            }
+            DrawDistribution::Weighted(weights) => {
+                let counts = weights.iter().filter(|&&(_, w)| w > 0).map(|&(c, _)| c);
+                Some((counts.clone().min()?, counts.max()?))
+            }
+        }
+    }
+
+    // needs `bounds` to be Some
+    fn sample(&self, rng: &mut SplitMix64) -> usize {
+        match self {
+            DrawDistribution::Uniform(range) => rng.range(range.clone()),
+            DrawDistribution::Weighted(weights) => {
+                // u32 weights only overflow a u64 total past 2^32 of them
+                let total: u64 = weights.iter().map(|&(_, w)| u64::from(w)).sum();
+                let mut pick = rng.next() % total;
+                weights
+                    .iter()
+                    .find(|&&(_, weight)| {
+                        let found = pick < u64::from(weight);
+                        pick = pick.saturating_sub(u64::from(weight));
+                        found
+                    })
+                    .map(|&(count, _)| count)
+                    .expect("picked a weight that is in the list")
+            }
+        }
+    }
+}
+
+// generated games and the answers both parts should give for them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedGames {
+    pub text: String,
+    pub part1: usize,
+    pub part2: usize,
+}
+
+/*/ Random games drawn from a bag, the same ones for the same seed. A draw takes
+a random handful of the cubes left in the bag, split between the colours at
+random, so even a bag of a trillion cubes draws as fast as a small one.
+Impossible games are drawn the same way, then one of their draws is given more
+of some colour than the bag holds.
+*/
+#[derive(Debug, Clone)]
+pub struct GameGenerator {
+    bag: BagLimits,
+    games: usize,
+    draws: DrawDistribution,
+    replacement: Replacement,
+    impossible_percent: u32,
+    seed: u64,
+}
+
+impl GameGenerator {
+    pub fn new(bag: BagLimits, games: usize, seed: u64) -> Self {
+        GameGenerator {
+            bag,
+            games,
+            draws: DrawDistribution::Uniform(1..=6),
+            replacement: Replacement::With,
+            impossible_percent: 0,
+            seed,
+        }
+    }
+
+    // how many draws a game has, picked evenly from `draws`
+    pub fn with_draws(self, draws: RangeInclusive<usize>) -> Self {
+        GameGenerator {
+            draws: DrawDistribution::Uniform(draws),
+            ..self
+        }
+    }
+
+    // how many draws a game has, each count picked as often as its weight
+    pub fn with_draw_weights(self, weights: impl IntoIterator<Item = (usize, u32)>) -> Self {
+        GameGenerator {
+            draws: DrawDistribution::Weighted(weights.into_iter().collect()),
+            ..self
+        }
+    }
+
+    pub fn with_replacement(self, replacement: Replacement) -> Self {
+        GameGenerator {
+            replacement,
+            ..self
+        }
+    }
+
+    // roughly how many games in a hundred can't be played with the bag
+    pub fn with_impossible(self, percent: u32) -> Self {
+        GameGenerator {
+            impossible_percent: percent,
+            ..self
+        }
+    }
+
+    pub fn generate(&self) -> Result<GeneratedGames> {
+        let cubes = self
+            .bag
+            .limits
+            .values()
+            .try_fold(0usize, |total, &count| total.checked_add(count))
+            .ok_or_else(|| AocError::overflow("cubes in the bag"))?;
+        let most_draws = match self.replacement {
+            Replacement::With => usize::MAX,
+            Replacement::Without => cubes,
+        };
+        let drawable = self
+            .draws
+            .bounds()
+            .is_some_and(|(fewest, most)| fewest > 0 && most <= most_draws);
+        if !drawable {
+            return Err(AocError::invalid(format!(
+                "cannot draw {:?} times from a bag of {cubes} cubes",
+                self.draws
+            )));
+        }
+        if cubes == 0 || self.impossible_percent > 100 {
+            return Err(AocError::invalid(format!(
+                "cannot make {}% of games impossible with a bag of {cubes} cubes",
+                self.impossible_percent
+            )));
+        }
+
+        let mut rng = SplitMix64(self.seed);
+        let mut generated = GeneratedGames {
+            text: String::new(),
+            part1: 0,
+            part2: 0,
+        };
+
+        for id in 1..=self.games {
+            let mut draws = self.draw_game(cubes, &mut rng);
+            let impossible = rng.range(1..=100) <= self.impossible_percent as usize;
+            if impossible {
+                let draw = rng.range(0..=draws.len() - 1);
+                let colours: Vec<&String> = self.bag.limits.keys().collect();
+                let colour = colours[rng.range(0..=colours.len() - 1)];
+                let limit = self.bag.limits[colour];
+                let over = limit.checked_add(rng.range(1..=3)).ok_or_else(|| {
+                    AocError::overflow(format!("more than the {limit} {colour} cubes in the bag"))
+                })?;
+                draws[draw].cubes.insert(colour.clone(), over);
+            }
+
+            let game = Game { id, draws };
+            let overflow = || AocError::overflow("generated answers");
+            if !impossible {
+                generated.part1 = generated.part1.checked_add(id).ok_or_else(overflow)?;
+            }
+            let power = game.power(self.bag.colours())?;
+            generated.part2 = generated.part2.checked_add(power).ok_or_else(overflow)?;
+
+            let _ = writeln!(generated.text, "{}", self.render(&game, &mut rng));
+        }
+
+        Ok(generated)
+    }
+
+    // `cubes` is how many the full bag holds
+    fn draw_game(&self, cubes: usize, rng: &mut SplitMix64) -> Vec<Draw> {
+        let draw_count = self.draws.sample(rng);
+        let full: Vec<(&str, usize)> = self.bag.colours().map(|c| (c, self.bag.limit(c))).collect();
+        let mut bag = full.clone();
+        let mut left = cubes;
+        let mut draws = vec![];
+
+        for drawn in 0..draw_count {
+            let handful = match self.replacement {
+                Replacement::With => {
+                    bag.clone_from(&full);
+                    left = cubes;
+                    rng.range(1..=left)
+                }
+                // leaving at least a cube for every draw still to come
+                Replacement::Without => rng.range(1..=left - (draw_count - drawn - 1)),
+            };
+
+            draws.push(take_handful(&mut bag, left, handful, rng));
+            left -= handful;
+        }
+
+        draws
+    }
+
+    // "Game 7: 3 blue, 4 red; 1 red, 2 green", colours in a random order
+    fn render(&self, game: &Game, rng: &mut SplitMix64) -> String {
+        let draws: Vec<String> = game
+            .draws
+            .iter()
+            .map(|draw| {
+                let mut cubes: Vec<String> = draw
+                    .cubes()
+                    .map(|(colour, count)| format!("{count} {colour}"))
+                    .collect();
+                rng.shuffle(&mut cubes);
+                cubes.join(", ")
+            })
+            .collect();
+
+        format!("Game {}: {}", game.id, draws.join("; "))
+    }
+}
+
+/*/ Takes `handful` of the `left` cubes in `bag` out of it. Colours are visited
+in a random order, each taking a random share of what is still to be drawn,
+but never so little that the colours after it couldn't make up the rest.
+*/
+fn take_handful(
+    bag: &mut [(&str, usize)],
+    mut left: usize,
+    mut handful: usize,
+    rng: &mut SplitMix64,
+) -> Draw {
+    let mut order: Vec<usize> = (0..bag.len()).collect();
+    rng.shuffle(&mut order);
+
+    let mut draw = Draw::default();
+    for ix in order {
+        let (colour, count) = &mut bag[ix];
+        // cubes of the colours still to come
+        left -= *count;
+        let taken = rng.range(handful.saturating_sub(left)..=handful.min(*count));
+        if taken > 0 {
+            draw.cubes.insert((*colour).to_owned(), taken);
+        }
+        *count -= taken;
+        handful -= taken;
+    }
+
+    draw
+}
+
+// the most cubes of each colour seen in a game, the bag's limits and the
+// running total, then the game ID
+pub type AggregatorFn = fn(
+    maxima: &BTreeMap<String, usize>,
+    limits: &BagLimits,
+    acc: usize,
+    id: usize,
+) -> Result<usize>;
+
+fn colour<'a>(input: &mut &'a str) -> PResult<&'a str> {
+    take_while(1.., char::is_alphabetic)
+        .context(parse::label("cube color"))
+        .parse_next(input)
+}
+
+// "3 blue"
+fn cubes<'a>(input: &mut &'a str) -> PResult<(usize, &'a str)> {
+    separated_pair(parse::unsigned, space1, colour).parse_next(input)
+}
+
+// a game's ID and the cubes of each of its draws, as written
+type GameCubes<'a> = (usize, Vec<Vec<(usize, &'a str)>>);
+
+// "Game 7: 3 blue, 4 red; 1 red, 2 green"
+fn game<'a>(input: &mut &'a str) -> PResult<GameCubes<'a>> {
+    let id = preceded(("Game", space1), parse::unsigned)
+        .context(parse::label("game ID"))
+        .parse_next(input)?;
+    let draws = preceded(
+        (space0, ':', space0),
+        parse::separated_by(parse::separated_by(cubes, ','), ';'),
+    )
+    .parse_next(input)?;
+
+    Ok((id, draws))
+}
+
+pub fn parse_file(
+    file_content: String,
+    limits: &BagLimits,
+    aggregator_fn: AggregatorFn,
+) -> Result<usize> {
+    let games: Games = file_content.parse()?;
+    let palette = games.palette(limits);
+
+    let total = games.iter().try_fold(0, |acc, game| {
+        aggregator_fn(&game.maxima(palette.iter().copied()), limits, acc, game.id)
+    })?;
+
+    Ok(total)
+}
+
+pub fn sum_possible_ids(
+    maxima: &BTreeMap<String, usize>,
+    limits: &BagLimits,
+    acc: usize,
+    id: usize,
+) -> Result<usize> {
+    if !limits.allows(maxima) {
+        return Ok(acc);
+    }
+
+    acc.checked_add(id)
+        .ok_or_else(|| AocError::overflow("sum of game IDs"))
+}
+
+pub fn sum_powers(
+    maxima: &BTreeMap<String, usize>,
+    _limits: &BagLimits,
+    acc: usize,
+    id: usize,
+) -> Result<usize> {
+    let power = maxima
+        .values()
+        .try_fold(1usize, |power, &count| power.checked_mul(count))
+        .ok_or_else(|| AocError::overflow(format!("power of game {id}")))?;
+
+    acc.checked_add(power)
+        .ok_or_else(|| AocError::overflow("sum of powers"))
+}
+
+pub struct Day2 {
+    games: Games,
+    limits: BagLimits,
+}
+
+impl Solution for Day2 {
+    fn parse(input: &Input) -> Result<Self> {
+        Ok(Day2 {
+            games: input.as_str().parse()?,
+            limits: BagLimits::standard(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(self.games.sum_possible_ids(&self.limits)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(self.games.sum_powers(&self.limits)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
 
-    fn load_file() -> String {
-        let mut file = File::open("src/fixtures/day2.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
+    #[test]
+    fn part2() {
+        let start = std::time::Instant::now();
 
-        file_contents
+        let games: Games = crate::fixture(2).unwrap().as_str().parse().unwrap();
+        let answer = games.sum_powers(&BagLimits::standard()).unwrap();
+        assert_eq!(answer, 71220usize);
+
+        let file_contents = crate::fixture(2).unwrap().into_string();
+        let folded = parse_file(file_contents, &BagLimits::standard(), sum_powers).unwrap();
+        assert_eq!(folded, answer);
+
+        println!("Process in: {:?}", start.elapsed());
     }
 
     #[test]
-    fn part2() {
+    fn part1() {
         let start = std::time::Instant::now();
 
-        fn aggregate(
-            red_max: usize,
-            green_max: usize,
-            blue_max: usize,
-            acc: usize,
-            _id: usize,
-        ) -> usize {
-            acc + (red_max * green_max * blue_max)
+        let games: Games = crate::fixture(2).unwrap().as_str().parse().unwrap();
+        let answer = games.sum_possible_ids(&BagLimits::standard()).unwrap();
+        assert_eq!(answer, 2377usize);
+
+        let file_contents = crate::fixture(2).unwrap().into_string();
+        let folded = parse_file(file_contents, &BagLimits::standard(), sum_possible_ids).unwrap();
+        assert_eq!(folded, answer);
+
+        println!("Process in: {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn game_queries() {
+        let games: Games = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"
+            .parse()
+            .unwrap();
+        let bag = BagLimits::standard();
+
+        assert_eq!(games.len(), 5);
+        assert_eq!(games.get(1).unwrap().draws.len(), 3);
+        assert_eq!(games.get(2).unwrap().draws[1].count("green"), 3);
+
+        let feasible: Vec<usize> = games.feasible(&bag).map(|game| game.id).collect();
+        assert_eq!(feasible, [1, 2, 5]);
+        assert_eq!(games.sum_possible_ids(&bag).unwrap(), 8);
+        assert_eq!(games.sum_powers(&bag).unwrap(), 2286);
+
+        let minimal: Vec<(usize, BagLimits)> = games.minimal_bags().collect();
+        assert_eq!(minimal[0].1, "4 red, 2 green, 6 blue".parse().unwrap());
+        assert_eq!(minimal[3].1, "14 red, 3 green, 15 blue".parse().unwrap());
+        assert!(games
+            .iter()
+            .all(|game| game.is_possible(&game.minimal_bag())));
+
+        let violations: Vec<Violation> = games.violations(&bag).collect();
+        assert_eq!(
+            violations,
+            [
+                Violation {
+                    game: 3,
+                    draw: 0,
+                    colour: "red".to_owned(),
+                    drawn: 20,
+                    limit: 12,
+                },
+                Violation {
+                    game: 4,
+                    draw: 2,
+                    colour: "blue".to_owned(),
+                    drawn: 15,
+                    limit: 14,
+                },
+            ]
+        );
+
+        let game1 = games.get(1).unwrap();
+        assert_eq!(game1.totals().unwrap()["blue"], 9);
+        assert_eq!(game1.averages().unwrap()["red"], 5.0 / 3.0);
+        assert_eq!(
+            games.totals().unwrap()["red"],
+            4 + 1 + 1 + 20 + 4 + 1 + 3 + 6 + 14 + 6 + 1
+        );
+        assert_eq!(games.averages().unwrap()["green"], 48.0 / 14.0);
+
+        // repeated colours in a draw add up
+        let repeated: Games = "Game 9: 3 red, 2 red".parse().unwrap();
+        assert_eq!(repeated.get(9).unwrap().draws[0].count("red"), 5);
+        assert!(Games::default().averages().unwrap().is_empty());
+
+        // so do repeats too big to count
+        let repeated = format!("Game 1: {} red, 1 red", usize::MAX);
+        assert!(matches!(
+            repeated.parse::<Games>(),
+            Err(AocError::Overflow { .. })
+        ));
+        let spread: Games = format!("Game 1: {} red; 1 red", usize::MAX)
+            .parse()
+            .unwrap();
+        for totals in [spread.totals(), spread.get(1).unwrap().totals()] {
+            assert!(matches!(totals, Err(AocError::Overflow { .. })));
         }
+        assert!(spread.averages().is_err());
+    }
 
-        let answer = parse_file(load_file(), aggregate);
-        assert_eq!(answer, 71220usize);
+    #[test]
+    fn malformed_games() {
+        let limits = BagLimits::standard();
+
+        let games = "Game 1: 3 blue, 4 red\nGame 2: 1 red, x green\n".to_owned();
+        let err = parse_file(games, &limits, sum_powers).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 16,
+                ..
+            }
+        ));
+
+        let games = "Game 1: 3 blue, 4 7".to_owned();
+        assert!(parse_file(games, &limits, sum_powers).is_err());
+
+        let games = "Game one: 3 blue".to_owned();
+        assert!(matches!(
+            parse_file(games, &limits, sum_powers),
+            Err(AocError::Parse {
+                line: 1,
+                column: 6,
+                ..
+            })
+        ));
+
+        // a game without green needs no green cubes
+        let games = "Game 1: 3 blue, 4 red".to_owned();
+        assert_eq!(parse_file(games, &limits, sum_powers).unwrap(), 0);
+    }
+
+    #[test]
+    fn aggregator_overflow() {
+        let limits = BagLimits::standard();
+
+        let games = format!("Game {}: 1 red\nGame 1: 1 red", usize::MAX);
+        assert!(matches!(
+            parse_file(games, &limits, sum_possible_ids),
+            Err(AocError::Overflow { .. })
+        ));
+
+        let games = format!("Game 1: {} red, 2 green, 1 blue", usize::MAX);
+        assert!(matches!(
+            parse_file(games, &limits, sum_powers),
+            Err(AocError::Overflow { .. })
+        ));
+
+        let games = format!(
+            "Game 1: {} red, 1 green, 1 blue\nGame 2: 1 red, 1 green, 1 blue",
+            usize::MAX
+        );
+        assert!(matches!(
+            parse_file(games, &limits, sum_powers),
+            Err(AocError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    fn bag_limits() {
+        let games = "Game 1: 3 blue, 4 purple; 1 red\n\
+                     Game 2: 2 red, 2 blue\n\
+                     Game 3: 9 purple, 1 blue, 5 red\n"
+            .to_owned();
+
+        // purple isn't in the standard bag, and every colour counts for power
+        let standard = BagLimits::standard();
+        assert_eq!(
+            parse_file(games.clone(), &standard, sum_possible_ids).unwrap(),
+            2
+        );
+        assert_eq!(parse_file(games.clone(), &standard, sum_powers).unwrap(), 0);
+
+        let purple: BagLimits = "5 red, 3 blue, 4 purple".parse().unwrap();
+        assert_eq!(purple.limit("purple"), 4);
+        assert_eq!(purple.limit("green"), 0);
+        assert_eq!(
+            parse_file(games.clone(), &purple, sum_possible_ids).unwrap(),
+            1 + 2
+        );
+
+        // without green in the bag or any game, powers use the three colours seen
+        let no_green =
+            "Game 1: 3 blue, 4 purple; 1 red\nGame 2: 2 red, 2 blue, 1 purple".to_owned();
+        assert_eq!(
+            parse_file(no_green, &purple, sum_powers).unwrap(),
+            3 * 4 + 2 * 2
+        );
+
+        assert_eq!(BagLimits::default(), standard);
+        assert_eq!(
+            " 12 red,13 green , 14 blue ".parse::<BagLimits>().unwrap(),
+            standard
+        );
+        assert!(matches!(
+            "1 red, 2 red".parse::<BagLimits>(),
+            Err(AocError::InvalidState { .. })
+        ));
+        assert!(matches!(
+            "1 red, two blue".parse::<BagLimits>(),
+            Err(AocError::Parse { column: 8, .. })
+        ));
+    }
+
+    #[test]
+    fn bag_inference() {
+        let games: Games = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"
+            .parse()
+            .unwrap();
+        let bag = BagLimits::standard();
+
+        let labels = games.iter().map(|game| (game.id, game.is_possible(&bag)));
+        let inferred = games.infer_bag(labels).unwrap().unwrap();
+        assert_eq!(
+            inferred.smallest(),
+            "6 red, 3 green, 6 blue".parse().unwrap()
+        );
+        assert!(inferred.contains(&bag));
+        assert!(!inferred.contains(&"20 red, 13 green, 6 blue".parse().unwrap()));
+        assert!(!inferred.contains(&"5 red, 3 green, 6 blue".parse().unwrap()));
+        // more of any one colour alone never makes game 3 or 4 possible
+        assert_eq!(inferred.upper_bound("red"), None);
+        assert!(!inferred.is_unique());
+        assert!(inferred.bags().is_none());
+
+        // the only split of the IDs into possible and impossible adding to 8
+        assert_eq!(games.infer_from_sum(8), [inferred]);
+        assert!(games.infer_from_sum(4).is_empty());
+        let all = games.infer_from_sum(15);
+        assert_eq!(all.len(), 1);
+        assert_eq!(
+            all[0].smallest(),
+            "20 red, 13 green, 15 blue".parse().unwrap()
+        );
+
+        assert!(matches!(
+            games.infer_bag([(6, true)]),
+            Err(AocError::InvalidState { .. })
+        ));
+    }
+
+    #[test]
+    fn bounded_bag_inference() {
+        let games: Games = "Game 1: 1 red, 1 blue
+Game 2: 3 red
+Game 3: 3 blue
+Game 4: 2 red; 2 blue
+Game 5: 2 red"
+            .parse()
+            .unwrap();
+
+        let inferred = games
+            .infer_bag([(1, true), (2, false), (3, false)])
+            .unwrap()
+            .unwrap();
+        assert_eq!(inferred.upper_bound("red"), Some(2));
+        assert_eq!(inferred.upper_bound("blue"), Some(2));
+        assert_eq!(inferred.bags().unwrap().count(), 4);
+
+        let inferred = games
+            .infer_bag([(1, true), (2, false), (3, false), (4, false)])
+            .unwrap()
+            .unwrap();
+        let bags: Vec<BagLimits> = inferred.bags().unwrap().collect();
+        assert_eq!(bags.len(), 3);
+        assert!(bags
+            .iter()
+            .all(|bag| bag.limit("red") + bag.limit("blue") < 4));
+
+        let unique = games
+            .infer_bag([(1, true), (5, true), (2, false), (3, false), (4, false)])
+            .unwrap()
+            .unwrap();
+        assert!(unique.is_unique());
+        assert_eq!(unique.smallest(), "2 red, 1 blue".parse().unwrap());
+        assert_eq!(
+            unique.bags().unwrap().collect::<Vec<_>>(),
+            [unique.smallest()]
+        );
+
+        // no bag has at least 2 red cubes and fewer than 2
+        assert_eq!(games.infer_bag([(4, true), (5, false)]).unwrap(), None);
+    }
+
+    #[test]
+    fn infer_fixture_bag() {
+        let start = std::time::Instant::now();
+
+        let games: Games = crate::fixture(2).unwrap().as_str().parse().unwrap();
+        let bag = BagLimits::standard();
+
+        let labels = games.iter().map(|game| (game.id, game.is_possible(&bag)));
+        let inferred = games.infer_bag(labels).unwrap().unwrap();
+        assert!(inferred.contains(&bag));
+        assert!(inferred.contains(&inferred.smallest()));
+        assert!(games.infer_from_sum(2377).contains(&inferred));
 
         println!("Process in: {:?}", start.elapsed());
     }
 
     #[test]
-    fn part1() {
+    fn generated_games() {
         let start = std::time::Instant::now();
+        let bag = BagLimits::standard();
 
-        fn aggregate(
-            red_max: usize,
-            green_max: usize,
-            blue_max: usize,
-            acc: usize,
-            id: usize,
-        ) -> usize {
-            if red_max <= RED_MAX && green_max <= GREEN_MAX && blue_max <= BLUE_MAX {
-                acc + id
-            } else {
-                acc
+        for replacement in [Replacement::With, Replacement::Without] {
+            for percent in [0, 30, 100] {
+                let generated = GameGenerator::new(bag.clone(), 1000, 2023)
+                    .with_draws(1..=8)
+                    .with_replacement(replacement)
+                    .with_impossible(percent)
+                    .generate()
+                    .unwrap();
+
+                let games: Games = generated.text.parse().unwrap();
+                assert_eq!(games.len(), 1000);
+                assert!(games.iter().all(|game| (1..=8).contains(&game.draws.len())));
+                assert_eq!(games.sum_possible_ids(&bag).unwrap(), generated.part1);
+                assert_eq!(games.sum_powers(&bag).unwrap(), generated.part2);
+
+                let text = generated.text.clone();
+                assert_eq!(
+                    parse_file(text, &bag, sum_possible_ids).unwrap(),
+                    generated.part1
+                );
+
+                match percent {
+                    0 => assert_eq!(generated.part1, 1000 * 1001 / 2),
+                    100 => assert_eq!(generated.part1, 0),
+                    _ => assert!(0 < generated.part1 && generated.part1 < 1000 * 1001 / 2),
+                }
+                if replacement == Replacement::Without && percent == 0 {
+                    assert!(games.iter().all(|game| bag.allows(&game.totals().unwrap())));
+                }
             }
         }
 
-        let answer = parse_file(load_file(), aggregate);
-        assert_eq!(answer, 2377usize);
+        println!("Process in: {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn generator_seeds() {
+        let bag: BagLimits = "2 red, 1 gold".parse().unwrap();
+        let generator = GameGenerator::new(bag.clone(), 20, 7).with_impossible(50);
+
+        assert_eq!(generator.generate().unwrap(), generator.generate().unwrap());
+        let other = GameGenerator::new(bag.clone(), 20, 8).with_impossible(50);
+        assert_ne!(
+            generator.generate().unwrap().text,
+            other.generate().unwrap().text
+        );
+
+        // three draws without replacement take every cube, a cube at a time
+        let generated = GameGenerator::new(bag.clone(), 5, 1)
+            .with_draws(3..=3)
+            .with_replacement(Replacement::Without)
+            .generate()
+            .unwrap();
+        let games: Games = generated.text.parse().unwrap();
+        assert!(games
+            .iter()
+            .all(|game| game.totals().unwrap() == bag.limits));
+        assert_eq!(generated.part2, 5);
+
+        let invalid = |generator: GameGenerator| {
+            matches!(generator.generate(), Err(AocError::InvalidState { .. }))
+        };
+        assert!(invalid(
+            GameGenerator::new(bag.clone(), 5, 1).with_draws(0..=2)
+        ));
+        assert!(invalid(
+            GameGenerator::new(bag.clone(), 5, 1)
+                .with_draws(1..=4)
+                .with_replacement(Replacement::Without)
+        ));
+        assert!(invalid(
+            GameGenerator::new(bag.clone(), 5, 1).with_impossible(101)
+        ));
+        assert!(invalid(GameGenerator::new("0 red".parse().unwrap(), 5, 1)));
+        assert!(invalid(
+            GameGenerator::new(bag.clone(), 5, 1).with_draw_weights([(2, 0)])
+        ));
+        assert!(invalid(
+            GameGenerator::new(bag.clone(), 5, 1).with_draw_weights([(0, 1), (1, 1)])
+        ));
+        assert!(invalid(
+            GameGenerator::new(bag.clone(), 5, 1)
+                .with_draw_weights([(1, 1), (4, 1)])
+                .with_replacement(Replacement::Without)
+        ));
+        assert_eq!(GameGenerator::new(bag, 0, 1).generate().unwrap().text, "");
+    }
+
+    #[test]
+    fn weighted_draws() {
+        let bag = BagLimits::standard();
+        let generated = GameGenerator::new(bag.clone(), 1000, 5)
+            .with_draw_weights([(1, 3), (2, 0), (6, 1)])
+            .generate()
+            .unwrap();
+        let games: Games = generated.text.parse().unwrap();
+
+        let single = games.iter().filter(|game| game.draws.len() == 1).count();
+        assert!(games.iter().all(|game| [1, 6].contains(&game.draws.len())));
+        assert!((650..850).contains(&single), "{single} single draw games");
+        assert_eq!(games.sum_possible_ids(&bag).unwrap(), generated.part1);
+    }
+
+    #[test]
+    fn huge_bags() {
+        let start = std::time::Instant::now();
+        // small enough a blue and green count that the powers still fit
+        let bag: BagLimits = "1000000000000 red, 1 green, 1000 blue".parse().unwrap();
+
+        for replacement in [Replacement::With, Replacement::Without] {
+            let generated = GameGenerator::new(bag.clone(), 200, 11)
+                .with_draws(1..=6)
+                .with_replacement(replacement)
+                .generate()
+                .unwrap();
+            let games: Games = generated.text.parse().unwrap();
+            assert_eq!(games.sum_possible_ids(&bag).unwrap(), generated.part1);
+            if replacement == Replacement::Without {
+                assert!(games.iter().all(|game| bag.allows(&game.totals().unwrap())));
+            }
+        }
+
+        let overflowing = |bag: &str, impossible| {
+            let generator = GameGenerator::new(bag.parse().unwrap(), 5, 1);
+            let result = generator.with_impossible(impossible).generate();
+            matches!(result, Err(AocError::Overflow { .. }))
+        };
+        assert!(overflowing(&format!("{} red, 1 blue", usize::MAX), 0));
+        assert!(overflowing(&format!("{} red", usize::MAX), 100));
+
+        let mut rng = SplitMix64(3);
+        assert_ne!(rng.range(0..=usize::MAX), rng.range(0..=usize::MAX));
 
         println!("Process in: {:?}", start.elapsed());
     }
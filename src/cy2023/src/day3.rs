@@ -2,138 +2,94 @@
 #![allow(non_snake_case)]
 #![allow(dead_code)]
 #![allow(unused_assignments)]
-use std::collections::{HashSet, VecDeque};
-use std::sync::OnceLock;
-
-type Location = (usize, usize);
-type LocationDeltas = (isize, isize);
-
-fn init_deltas() -> &'static [LocationDeltas; 8] {
-    static SCAN_POS_DELTAS: OnceLock<[LocationDeltas; 8]> = OnceLock::new();
-    SCAN_POS_DELTAS.get_or_init(|| {
-        [
-            (-1, 0),  //Up
-            (-1, 1),  //UpDiagRight
-            (0, 1),   //Right
-            (1, 1),   //DownDiagRight
-            (1, 0),   //Down
-            (1, -1),  //DownDiagLeft
-            (0, -1),  //Left
-            (-1, -1), //UpDiagLeft
-        ]
-    })
-}
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::ops::Range;
 
-fn get_scan_destinations(
-    curr_position: Location,
-    m: usize,
-    n: usize,
-) -> impl Iterator<Item = Location> {
-    let ld = init_deltas();
-    let (curr_row, curr_col) = curr_position;
-
-    ld.iter().filter_map(move |(r, c)| {
-        let new_row = curr_row as isize + r;
-        let new_col = curr_col as isize + c;
-
-        if new_row >= 0 && new_row < m as isize && new_col >= 0 && new_col < n as isize {
-            Some((new_row as usize, new_col as usize))
-        } else {
-            None
-        }
-    })
-}
+use serde::Serialize;
+use tracing::debug;
 
-fn is_part_number(curr_position: Location, grid: &Vec<Vec<char>>) -> bool {
-    let m = grid.len();
-    let n = grid[0].len();
+use aoc_core::error::{AocError, Result};
+use aoc_core::grid::{Grid, Point};
+use aoc_core::input::Input;
+use aoc_core::solution::{Answer, Solution};
 
-    let tf = get_scan_destinations(curr_position, m, n)
-        // .inspect(move |x| println!("Destinations for {} are: {}", &curr_position, &x))
-        .any(|(mnext, nnext)| match grid[mnext][nnext] {
-            '*' | '@' | '#' | '$' | '%' | '&' | '/' | '=' | '+' | '-' => true,
-            _ => false,
-        });
+type Location = Point;
+
+fn is_part_number(curr_position: Location, grid: &Grid<char>) -> bool {
+    grid.neighbours8(curr_position)
+        .any(|loc| SymbolSet::Puzzle.contains(grid[loc]))
+}
 
-    tf
+// digits only ever fail to parse when the number is too large
+fn to_number(digits: &str) -> Result<usize> {
+    digits
+        .parse()
+        .map_err(|_| AocError::overflow(format!("part number {digits}")))
 }
 
 fn read_number(
     loc: Location,
-    grid: &Vec<Vec<char>>,
+    grid: &Grid<char>,
     seen_digit_locs: &mut HashSet<Location>,
     adjacent_gears: &mut VecDeque<Location>,
     seen_gear_locs: &mut HashSet<Location>,
-    M: usize,
-    N: usize,
-) -> Option<usize> {
+) -> Result<Option<usize>> {
     // read far left on number and then read entire number to right
     let mut number_scanned = String::new();
     let (m, n) = loc;
 
     for n_bak in (0..n).rev() {
-        if !(grid[m][n_bak].is_digit(10)) {
-            for n_fwd in n_bak + 1..grid[0].len() {
-                if grid[m][n_fwd].is_digit(10) {
+        if !grid[(m, n_bak)].is_ascii_digit() {
+            for n_fwd in n_bak + 1..grid.width() {
+                if grid[(m, n_fwd)].is_ascii_digit() {
                     seen_digit_locs.insert((m, n_fwd));
-                    number_scanned.push(grid[m][n_fwd]);
-
-                    let dests =
-                        get_scan_destinations((m, n_fwd), M, N).filter_map(|loc| {
-                            match grid[loc.0][loc.1] {
-                                '*' if !seen_gear_locs.contains(&loc) => {
-                                    seen_digit_locs.insert((m, n));
-                                    Some(loc)
-                                }
-                                _ => None,
-                            }
-                        });
+                    number_scanned.push(grid[(m, n_fwd)]);
+
+                    let dests = grid.neighbours8((m, n_fwd)).filter(|&loc| match grid[loc] {
+                        '*' if !seen_gear_locs.contains(&loc) => {
+                            seen_digit_locs.insert((m, n));
+                            true
+                        }
+                        _ => false,
+                    });
                     adjacent_gears.extend(dests);
                 } else {
-                    if number_scanned.len() > 0 {
-                        let num_r = number_scanned.parse::<usize>().unwrap();
-                        return Some(num_r);
+                    if !number_scanned.is_empty() {
+                        return to_number(&number_scanned).map(Some);
                     }
 
-                    return None;
+                    return Ok(None);
                 }
             }
         } else if n_bak == 0 {
-            for n_fwd in n_bak..grid[0].len() {
-                if grid[m][n_fwd].is_digit(10) {
+            for n_fwd in n_bak..grid.width() {
+                if grid[(m, n_fwd)].is_ascii_digit() {
                     seen_digit_locs.insert((m, n_fwd));
-                    number_scanned.push(grid[m][n_fwd]);
-
-                    let dests =
-                        get_scan_destinations((m, n_fwd), M, N).filter_map(|loc| {
-                            match grid[loc.0][loc.1] {
-                                '*' if !seen_gear_locs.contains(&loc) => {
-                                    seen_digit_locs.insert((m, n));
-                                    Some(loc)
-                                }
-                                _ => None,
-                            }
-                        });
+                    number_scanned.push(grid[(m, n_fwd)]);
+
+                    let dests = grid.neighbours8((m, n_fwd)).filter(|&loc| match grid[loc] {
+                        '*' if !seen_gear_locs.contains(&loc) => {
+                            seen_digit_locs.insert((m, n));
+                            true
+                        }
+                        _ => false,
+                    });
                     adjacent_gears.extend(dests);
                 } else {
-                    if number_scanned.len() > 0 {
-                        let num_r = number_scanned.parse::<usize>().unwrap();
-                        return Some(num_r);
+                    if !number_scanned.is_empty() {
+                        return to_number(&number_scanned).map(Some);
                     }
 
-                    return None;
+                    return Ok(None);
                 }
             }
         }
     }
 
-    None
+    Ok(None)
 }
 
-pub fn parse_gears(grid: &Vec<Vec<char>>) -> usize {
-    let M = grid.len();
-    let N = grid[0].len();
-
+pub fn parse_gears(grid: &Grid<char>) -> Result<usize> {
     let mut solution: usize = 0;
     let mut running_numbers_list: Vec<usize> = vec![];
     let mut seen_digit_locs: HashSet<Location> = HashSet::new();
@@ -141,7 +97,7 @@ pub fn parse_gears(grid: &Vec<Vec<char>>) -> usize {
     let mut adjacent_gears: VecDeque<Location> = VecDeque::new();
     let mut adjacent_nums: VecDeque<Location> = VecDeque::new();
 
-    for (m, row) in grid.iter().enumerate() {
+    for (m, row) in grid.rows().enumerate() {
         for (n, b) in row.iter().enumerate() {
             if seen_gear_locs.contains(&(m, n)) || seen_digit_locs.contains(&(m, n)) {
                 continue;
@@ -156,11 +112,9 @@ pub fn parse_gears(grid: &Vec<Vec<char>>) -> usize {
 
                         // get candidate locations for surrounding
                         // numbers and push on to queue
-                        let dests = get_scan_destinations(gear, M, N).filter_map(|loc| match grid
-                            [loc.0][loc.1]
-                        {
-                            '0'..='9' if !seen_digit_locs.contains(&loc) => Some(loc),
-                            _ => None,
+                        let dests = grid.neighbours8(gear).filter(|&loc| match grid[loc] {
+                            '0'..='9' => !seen_digit_locs.contains(&loc),
+                            _ => false,
                         });
 
                         adjacent_nums.extend(dests);
@@ -173,9 +127,7 @@ pub fn parse_gears(grid: &Vec<Vec<char>>) -> usize {
                                     &mut seen_digit_locs,
                                     &mut adjacent_gears,
                                     &mut seen_gear_locs,
-                                    M,
-                                    N,
-                                ) {
+                                )? {
                                     running_numbers_list.push(num);
                                 }
                             }
@@ -184,8 +136,11 @@ pub fn parse_gears(grid: &Vec<Vec<char>>) -> usize {
                     if running_numbers_list.len() > 1 {
                         // we have more than one number surrounding this gear
                         // multiply them all and add to running power value
-                        solution =
-                            solution + running_numbers_list.iter().fold(1, |acc, num| num * acc);
+                        let ratio = running_numbers_list
+                            .iter()
+                            .try_fold(1usize, |acc, num| acc.checked_mul(*num))
+                            .and_then(|ratio| solution.checked_add(ratio));
+                        solution = ratio.ok_or_else(|| AocError::overflow("gear ratio total"))?;
                     }
                     running_numbers_list.clear();
                 }
@@ -194,34 +149,33 @@ pub fn parse_gears(grid: &Vec<Vec<char>>) -> usize {
         }
     }
 
-    solution
+    Ok(solution)
 }
 
-pub fn parse_file(grid: &Vec<Vec<char>>) -> usize {
+pub fn parse_file(grid: &Grid<char>) -> Result<usize> {
     let mut running_number = String::new();
     let mut valid_numbers: Vec<usize> = vec![];
 
     let mut part_found = false;
-    for (m, row) in grid.iter().enumerate() {
+    for (m, row) in grid.rows().enumerate() {
         for (n, b) in row.iter().enumerate() {
             // println!("Loc: {:?}, candidate : {}", &(m, n), &b);
             match b {
                 '0'..='9' => {
-                    running_number.push(b.clone());
+                    running_number.push(*b);
 
                     if !part_found {
                         // println!("Checking is part...");
-                        if is_part_number((m, n), &grid) {
+                        if is_part_number((m, n), grid) {
                             part_found = true;
                         }
                     }
                 }
                 _ => {
                     // println!("Other match: {}", b);
-                    if part_found && running_number.len() > 0 {
+                    if part_found && !running_number.is_empty() {
                         // println!("Saving number: {}", running_number);
-                        valid_numbers
-                            .push(running_number.parse().expect("Should be valid a number"));
+                        valid_numbers.push(to_number(&running_number)?);
                     }
                     running_number.clear();
                     part_found = false;
@@ -229,40 +183,431 @@ pub fn parse_file(grid: &Vec<Vec<char>>) -> usize {
             }
         }
 
-        if part_found && running_number.len() > 0 {
+        if part_found && !running_number.is_empty() {
             // println!("Saving number: {}", running_number);
-            valid_numbers.push(running_number.parse().expect("Should be valid a number"));
+            valid_numbers.push(to_number(&running_number)?);
         }
         running_number.clear();
         part_found = false;
     }
 
-    if part_found && running_number.len() > 0 {
+    if part_found && !running_number.is_empty() {
         // println!("Saving number: {}", running_number);
-        valid_numbers.push(running_number.parse().expect("Should be valid a number"));
+        valid_numbers.push(to_number(&running_number)?);
     }
 
-    println!("valid_numbers: {:?}", &valid_numbers);
+    debug!(?valid_numbers, "part numbers");
 
-    valid_numbers.iter().sum()
+    valid_numbers
+        .iter()
+        .try_fold(0usize, |acc, num| acc.checked_add(*num))
+        .ok_or_else(|| AocError::overflow("part number total"))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
+// the symbols the puzzle marks parts with
+const PUZZLE_SYMBOLS: [char; 10] = ['*', '@', '#', '$', '%', '&', '/', '=', '+', '-'];
+
+// which characters of a schematic count as symbols
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolSet {
+    // the ten symbols the puzzle uses
+    Puzzle,
+    // anything that isn't a digit or '.'
+    AnyMark,
+    Chars(BTreeSet<char>),
+}
+
+impl SymbolSet {
+    pub fn chars(chars: impl IntoIterator<Item = char>) -> Self {
+        SymbolSet::Chars(chars.into_iter().collect())
+    }
+
+    pub fn contains(&self, ch: char) -> bool {
+        match self {
+            SymbolSet::Puzzle => PUZZLE_SYMBOLS.contains(&ch),
+            SymbolSet::AnyMark => ch != '.' && !ch.is_ascii_digit(),
+            SymbolSet::Chars(chars) => chars.contains(&ch),
+        }
+    }
+}
+
+// how many different numbers a gear has to touch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbours {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Neighbours {
+    fn allows(self, count: usize) -> bool {
+        match self {
+            Neighbours::Exactly(n) => count == n,
+            Neighbours::AtLeast(n) => count >= n,
+        }
+    }
+}
+
+// what a gear makes of the numbers around it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reducer {
+    Product,
+    Sum,
+    Min,
+    Max,
+}
 
-    fn load_file() -> String {
-        let mut file = File::open("src/fixtures/day3.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
+impl Reducer {
+    fn reduce(self, numbers: &[usize]) -> Option<usize> {
+        match self {
+            Reducer::Product => numbers
+                .iter()
+                .try_fold(1usize, |acc, &num| acc.checked_mul(num)),
+            Reducer::Sum => numbers
+                .iter()
+                .try_fold(0usize, |acc, &num| acc.checked_add(num)),
+            Reducer::Min => numbers.iter().copied().min(),
+            Reducer::Max => numbers.iter().copied().max(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbols: SymbolSet,
+    pub neighbours: Neighbours,
+    pub reducer: Reducer,
+}
 
-        init_deltas();
+/*/ What counts as a symbol when finding part numbers, and the gear rules.
+Each symbol is a gear under the first rule it matches, and a gear touching
+the right number of numbers adds what its reducer makes of them to the
+total. A symbol touching no numbers is never a gear, and a number touching
+a symbol more than once still counts once.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicRules {
+    symbols: SymbolSet,
+    gears: Vec<GearRule>,
+}
+
+impl Default for SchematicRules {
+    fn default() -> Self {
+        SchematicRules::puzzle()
+    }
+}
+
+impl SchematicRules {
+    pub fn new(symbols: SymbolSet) -> Self {
+        SchematicRules {
+            symbols,
+            gears: vec![],
+        }
+    }
+
+    // the puzzle's symbols, and a '*' touching two or more numbers is a gear
+    // worth their product
+    pub fn puzzle() -> Self {
+        SchematicRules::new(SymbolSet::Puzzle).with_gear(
+            SymbolSet::chars(['*']),
+            Neighbours::AtLeast(2),
+            Reducer::Product,
+        )
+    }
+
+    pub fn with_gear(
+        mut self,
+        symbols: SymbolSet,
+        neighbours: Neighbours,
+        reducer: Reducer,
+    ) -> Self {
+        self.gears.push(GearRule {
+            symbols,
+            neighbours,
+            reducer,
+        });
+        self
+    }
+
+    pub fn symbols(&self) -> &SymbolSet {
+        &self.symbols
+    }
+
+    pub fn gears(&self) -> &[GearRule] {
+        &self.gears
+    }
+
+    // sum of every number touching a symbol
+    pub fn part_sum(&self, grid: &Grid<char>) -> Result<usize> {
+        Schematic::new(grid, &self.symbols)?
+            .part_numbers()
+            .try_fold(0usize, |acc, number| acc.checked_add(number.value))
+            .ok_or_else(|| AocError::overflow("part number total"))
+    }
+
+    pub fn gear_total(&self, grid: &Grid<char>) -> Result<usize> {
+        let schematic = Schematic::with_symbols(grid, |ch| {
+            self.gears.iter().any(|rule| rule.symbols.contains(ch))
+        })?;
+
+        schematic
+            .symbols()
+            .iter()
+            .try_fold(0usize, |total, symbol| {
+                let rule = self
+                    .gears
+                    .iter()
+                    .find(|rule| rule.symbols.contains(symbol.symbol))
+                    .expect("only symbols some gear rule matches are in the schematic");
+
+                let values: Vec<usize> = schematic
+                    .numbers_touching(symbol.loc())
+                    .map(|number| number.value)
+                    .collect();
+                if values.is_empty() || !rule.neighbours.allows(values.len()) {
+                    return Ok(total);
+                }
+
+                rule.reducer
+                    .reduce(&values)
+                    .and_then(|value| total.checked_add(value))
+                    .ok_or_else(|| AocError::overflow("gear total"))
+            })
+    }
+}
+
+// a run of digits along a row, `cols` is the columns it covers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Number {
+    pub value: usize,
+    pub row: usize,
+    pub cols: Range<usize>,
+}
+
+impl Number {
+    pub fn cells(&self) -> impl Iterator<Item = Location> + '_ {
+        self.cols.clone().map(|col| (self.row, col))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Symbol {
+    pub symbol: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Symbol {
+    pub fn loc(&self) -> Location {
+        (self.row, self.col)
+    }
+}
+
+// a number and a symbol touching it, as indexes into the schematic's lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Edge {
+    pub number: usize,
+    pub symbol: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Number(usize),
+    Symbol(usize),
+}
+
+/*/ Every number and symbol of an engine schematic and which of them touch,
+diagonals included. Numbers are read left to right, top to bottom, and so
+are symbols. The touching pairs form a graph with numbers on one side and
+symbols on the other, where a number can touch any number of symbols and a
+symbol any number of numbers.
+*/
+#[derive(Debug, Clone, Serialize)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    edges: Vec<Edge>,
+    #[serde(skip)]
+    cells: Grid<Option<Cell>>,
+    #[serde(skip)]
+    number_symbols: Vec<Vec<usize>>,
+    #[serde(skip)]
+    symbol_numbers: Vec<Vec<usize>>,
+}
 
-        file_contents
+impl Schematic {
+    pub fn new(grid: &Grid<char>, symbols: &SymbolSet) -> Result<Self> {
+        Schematic::with_symbols(grid, |ch| symbols.contains(ch))
     }
 
+    // digits are always part of a number, never a symbol
+    fn with_symbols(grid: &Grid<char>, is_symbol: impl Fn(char) -> bool) -> Result<Self> {
+        let mut schematic = Schematic {
+            numbers: vec![],
+            symbols: vec![],
+            edges: vec![],
+            cells: grid.map(|_| None),
+            number_symbols: vec![],
+            symbol_numbers: vec![],
+        };
+
+        for (row, cells) in grid.rows().enumerate() {
+            let mut col = 0;
+            while col < cells.len() {
+                let ch = cells[col];
+                if !ch.is_ascii_digit() {
+                    if is_symbol(ch) {
+                        schematic.cells[(row, col)] = Some(Cell::Symbol(schematic.symbols.len()));
+                        schematic.symbols.push(Symbol {
+                            symbol: ch,
+                            row,
+                            col,
+                        });
+                    }
+                    col += 1;
+                    continue;
+                }
+
+                let end = cells[col..]
+                    .iter()
+                    .position(|ch| !ch.is_ascii_digit())
+                    .map_or(cells.len(), |len| col + len);
+                let digits: String = cells[col..end].iter().collect();
+                let number = Number {
+                    value: to_number(&digits)?,
+                    row,
+                    cols: col..end,
+                };
+                for cell in number.cells() {
+                    schematic.cells[cell] = Some(Cell::Number(schematic.numbers.len()));
+                }
+                schematic.numbers.push(number);
+                col = end;
+            }
+        }
+
+        schematic.number_symbols = vec![vec![]; schematic.numbers.len()];
+        schematic.symbol_numbers = vec![vec![]; schematic.symbols.len()];
+        for (ix, symbol) in schematic.symbols.iter().enumerate() {
+            let mut touching: Vec<usize> = grid
+                .neighbours8(symbol.loc())
+                .filter_map(|loc| match schematic.cells[loc] {
+                    Some(Cell::Number(number)) => Some(number),
+                    _ => None,
+                })
+                .collect();
+            touching.sort_unstable();
+            touching.dedup();
+
+            for &number in &touching {
+                schematic.number_symbols[number].push(ix);
+                schematic.edges.push(Edge { number, symbol: ix });
+            }
+            schematic.symbol_numbers[ix] = touching;
+        }
+        schematic
+            .edges
+            .sort_unstable_by_key(|edge| (edge.number, edge.symbol));
+
+        Ok(schematic)
+    }
+
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    // ordered by number, then symbol
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    // the number with a digit at `loc`
+    pub fn number_at(&self, loc: Location) -> Option<&Number> {
+        match self.cells.get(loc)? {
+            Some(Cell::Number(ix)) => Some(&self.numbers[*ix]),
+            _ => None,
+        }
+    }
+
+    pub fn symbol_at(&self, loc: Location) -> Option<&Symbol> {
+        match self.cells.get(loc)? {
+            Some(Cell::Symbol(ix)) => Some(&self.symbols[*ix]),
+            _ => None,
+        }
+    }
+
+    // the symbols touching the number with a digit at `loc`
+    pub fn symbols_touching(&self, loc: Location) -> impl Iterator<Item = &Symbol> {
+        let touching = match self.cells.get(loc) {
+            Some(Some(Cell::Number(ix))) => self.number_symbols[*ix].as_slice(),
+            _ => &[],
+        };
+        touching.iter().map(|&ix| &self.symbols[ix])
+    }
+
+    // the numbers touching the symbol at `loc`
+    pub fn numbers_touching(&self, loc: Location) -> impl Iterator<Item = &Number> {
+        let touching = match self.cells.get(loc) {
+            Some(Some(Cell::Symbol(ix))) => self.symbol_numbers[*ix].as_slice(),
+            _ => &[],
+        };
+        touching.iter().map(|&ix| &self.numbers[ix])
+    }
+
+    // numbers touching at least one symbol
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.number_symbols)
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number)
+    }
+
+    // numbers touching more than one symbol
+    pub fn shared_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.number_symbols)
+            .filter(|(_, symbols)| symbols.len() > 1)
+            .map(|(number, _)| number)
+    }
+
+    pub fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+pub struct Day3 {
+    grid: Grid<char>,
+    rules: SchematicRules,
+}
+
+impl Solution for Day3 {
+    fn parse(input: &Input) -> Result<Self> {
+        let grid = input.char_grid()?;
+
+        Ok(Day3 {
+            grid,
+            rules: SchematicRules::puzzle(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(self.rules.part_sum(&self.grid)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(self.rules.gear_total(&self.grid)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn day3_gear() {
         let start = std::time::Instant::now();
@@ -277,10 +622,9 @@ mod tests {
 ...$.*....
 .664.598.."#;
 
-        let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-        init_deltas();
+        let grid: Grid<char> = input.parse().unwrap();
 
-        let answer = parse_gears(&grid);
+        let answer = parse_gears(&grid).unwrap();
         assert_eq!(answer, 467835usize);
 
         println!("Process in: {:?}", start.elapsed());
@@ -290,9 +634,8 @@ mod tests {
     fn day3_part2() {
         let start = std::time::Instant::now();
 
-        let input = load_file();
-        let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-        let answer = parse_gears(&grid);
+        let grid = crate::fixture(3).unwrap().char_grid().unwrap();
+        let answer = parse_gears(&grid).unwrap();
 
         assert_eq!(answer, 78915902usize);
 
@@ -303,9 +646,8 @@ mod tests {
     fn day3_part1() {
         let start = std::time::Instant::now();
 
-        let input = load_file();
-        let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-        let answer = parse_file(&grid);
+        let grid = crate::fixture(3).unwrap().char_grid().unwrap();
+        let answer = parse_file(&grid).unwrap();
 
         assert_eq!(answer, 514969usize);
 
@@ -328,10 +670,9 @@ mod tests {
 .664*598..
 "#;
 
-        let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-        init_deltas();
+        let grid: Grid<char> = input.parse().unwrap();
 
-        let answer = parse_file(&grid);
+        let answer = parse_file(&grid).unwrap();
         assert_eq!(answer, 4361usize);
 
         println!("Process in: {:?}", start.elapsed());
@@ -353,10 +694,9 @@ mod tests {
 .664.598..
 "#;
 
-        let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-        init_deltas();
+        let grid: Grid<char> = input.parse().unwrap();
 
-        let answer = parse_file(&grid);
+        let answer = parse_file(&grid).unwrap();
         assert_eq!(answer, 4361usize);
 
         println!("Process in: {:?}", start.elapsed());
@@ -378,12 +718,172 @@ mod tests {
 .664.598..
 "#;
 
-        let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-        init_deltas();
+        let grid: Grid<char> = input.parse().unwrap();
 
-        let answer = parse_file(&grid);
+        let answer = parse_file(&grid).unwrap();
         assert_eq!(answer, 4361usize);
 
         println!("Process in: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn part_number_overflow() {
+        let grid: Grid<char> = "99999999999999999999999*".parse().unwrap();
+
+        assert!(matches!(parse_file(&grid), Err(AocError::Overflow { .. })));
+        assert!(matches!(parse_gears(&grid), Err(AocError::Overflow { .. })));
+    }
+
+    const SAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    #[test]
+    fn puzzle_rules() {
+        let start = std::time::Instant::now();
+        let rules = SchematicRules::puzzle();
+
+        let sample: Grid<char> = SAMPLE.parse().unwrap();
+        assert_eq!(rules.part_sum(&sample).unwrap(), 4361);
+        assert_eq!(rules.gear_total(&sample).unwrap(), 467835);
+
+        let grid = crate::fixture(3).unwrap().char_grid().unwrap();
+        assert_eq!(rules.part_sum(&grid).unwrap(), parse_file(&grid).unwrap());
+        assert_eq!(
+            rules.gear_total(&grid).unwrap(),
+            parse_gears(&grid).unwrap()
+        );
+
+        println!("Process in: {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn variant_rules() {
+        let grid: Grid<char> = "12.3\n..?.\n4+5*\n..6.".parse().unwrap();
+
+        // '?' is no puzzle symbol, and 12 and 3 only touch it
+        let any_mark = SchematicRules::new(SymbolSet::AnyMark);
+        assert_eq!(SchematicRules::puzzle().part_sum(&grid).unwrap(), 4 + 5 + 6);
+        assert_eq!(any_mark.part_sum(&grid).unwrap(), 12 + 3 + 4 + 5 + 6);
+        let question = SchematicRules::new(SymbolSet::chars(['?']));
+        assert_eq!(question.part_sum(&grid).unwrap(), 12 + 3 + 5);
+
+        // '+' touches 4, 5 and 6, '*' touches 5 and 6, '?' touches 12, 3 and 5
+        let sums = SchematicRules::new(SymbolSet::AnyMark)
+            .with_gear(
+                SymbolSet::chars(['+']),
+                Neighbours::AtLeast(1),
+                Reducer::Sum,
+            )
+            .with_gear(
+                SymbolSet::chars(['*']),
+                Neighbours::Exactly(2),
+                Reducer::Product,
+            )
+            .with_gear(SymbolSet::AnyMark, Neighbours::Exactly(3), Reducer::Max);
+        assert_eq!(sums.gear_total(&grid).unwrap(), (4 + 5 + 6) + 5 * 6 + 12);
+
+        // the first rule a symbol matches is the one it follows
+        let first = SchematicRules::new(SymbolSet::AnyMark)
+            .with_gear(SymbolSet::AnyMark, Neighbours::Exactly(3), Reducer::Min)
+            .with_gear(
+                SymbolSet::chars(['*']),
+                Neighbours::Exactly(2),
+                Reducer::Sum,
+            );
+        assert_eq!(first.gear_total(&grid).unwrap(), 4 + 3);
+
+        assert_eq!(SchematicRules::puzzle().gear_total(&grid).unwrap(), 5 * 6);
+        assert_eq!(SchematicRules::default(), SchematicRules::puzzle());
+        assert!(SchematicRules::new(SymbolSet::Puzzle).gears().is_empty());
+
+        let wide: Grid<char> = "99999999999*\n99999999999.".parse().unwrap();
+        let rules = SchematicRules::new(SymbolSet::Puzzle).with_gear(
+            SymbolSet::Puzzle,
+            Neighbours::AtLeast(2),
+            Reducer::Product,
+        );
+        assert!(matches!(
+            rules.gear_total(&wide),
+            Err(AocError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    fn schematic_graph() {
+        let sample: Grid<char> = SAMPLE.parse().unwrap();
+        let schematic = Schematic::new(&sample, &SymbolSet::Puzzle).unwrap();
+
+        assert_eq!(schematic.numbers().len(), 10);
+        assert_eq!(schematic.symbols().len(), 6);
+        assert_eq!(
+            schematic.number_at((0, 1)),
+            Some(&Number {
+                value: 467,
+                row: 0,
+                cols: 0..3,
+            })
+        );
+        assert_eq!(schematic.number_at((0, 3)), None);
+        assert_eq!(schematic.symbol_at((3, 6)).unwrap().symbol, '#');
+
+        let touching: Vec<Location> = schematic
+            .symbols_touching((0, 2))
+            .map(Symbol::loc)
+            .collect();
+        assert_eq!(touching, [(1, 3)]);
+        assert_eq!(schematic.symbols_touching((0, 5)).count(), 0);
+        assert_eq!(schematic.symbols_touching((1, 3)).count(), 0);
+
+        let gear: Vec<usize> = schematic
+            .numbers_touching((8, 5))
+            .map(|n| n.value)
+            .collect();
+        assert_eq!(gear, [755, 598]);
+
+        let parts: usize = schematic.part_numbers().map(|n| n.value).sum();
+        assert_eq!(parts, 4361);
+        assert_eq!(schematic.edges().len(), 8);
+        assert_eq!(schematic.shared_numbers().count(), 0);
+
+        // 12 is shared by both symbols, 7 touches only the '#'
+        let grid: Grid<char> = "*12#\n...7".parse().unwrap();
+        let schematic = Schematic::new(&grid, &SymbolSet::AnyMark).unwrap();
+        let shared: Vec<usize> = schematic.shared_numbers().map(|n| n.value).collect();
+        assert_eq!(shared, [12]);
+        assert_eq!(
+            schematic.edges(),
+            [
+                Edge {
+                    number: 0,
+                    symbol: 0,
+                },
+                Edge {
+                    number: 0,
+                    symbol: 1,
+                },
+                Edge {
+                    number: 1,
+                    symbol: 1,
+                },
+            ]
+        );
+        assert_eq!(schematic.symbols_touching((0, 2)).count(), 2);
+
+        let json: serde_json::Value = serde_json::from_str(&schematic.json().unwrap()).unwrap();
+        assert_eq!(json["numbers"][0]["value"], 12);
+        assert_eq!(json["numbers"][0]["cols"]["start"], 1);
+        assert_eq!(json["numbers"][0]["cols"]["end"], 3);
+        assert_eq!(json["symbols"][1]["symbol"], "#");
+        assert_eq!(json["edges"][2]["number"], 1);
+        assert!(json.get("cells").is_none());
+    }
 }
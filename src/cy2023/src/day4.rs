@@ -1,56 +1,101 @@
 use std::collections::HashSet;
 
-use winnow::ascii::{digit1, line_ending, multispace1 as multispace};
-use winnow::combinator::{alt, eof, opt, preceded, repeat};
-use winnow::token::take_till;
+use winnow::ascii::space1;
+use winnow::combinator::{cut_err, repeat};
 use winnow::{PResult, Parser};
 
-pub fn card_line_prefix<'a>(input: &mut &'a str) -> PResult<()> {
-    let _ = "Card".parse_next(input)?;
-    let _ = take_till(0.., |w| w == ':').parse_next(input)?;
-    let _ = ':'.parse_next(input)?;
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
 
-    Ok(())
+type Card = (HashSet<usize>, Vec<usize>);
+
+pub fn card_line_prefix(input: &mut &str) -> PResult<usize> {
+    ("Card", space1, parse::unsigned::<usize>, ':')
+        .map(|(_, _, id, _)| id)
+        .context(parse::label("card header"))
+        .parse_next(input)
 }
 
-pub fn number<'a>(input: &mut &'a str) -> PResult<usize> {
-    let _ = opt(multispace).parse_next(input)?;
-    digit1.parse_to().parse_next(input)
+pub fn card_line(input: &mut &str) -> PResult<Card> {
+    let _ = card_line_prefix.parse_next(input)?;
+    let (winners, _, ours) = cut_err(parse::line((
+        parse::unsigned_list,
+        '|',
+        parse::unsigned_list,
+    )))
+    .parse_next(input)?;
+
+    Ok((HashSet::from_iter(winners), ours))
 }
 
-pub fn numbers<'a>(input: &mut &'a str) -> PResult<Vec<usize>> {
-    repeat(0.., number).parse_next(input)
+pub fn parse_stack(input: &mut &str) -> PResult<Vec<Card>> {
+    repeat(0.., card_line).parse_next(input)
 }
 
-pub fn bar_sep<'a>(input: &mut &'a str) -> PResult<()> {
-    let _ = multispace.parse_next(input)?;
-    '|'.parse_next(input)?;
-    let _ = multispace.parse_next(input)?;
+fn matching_numbers((winners, ours): &Card) -> usize {
+    ours.iter().filter(|o| winners.contains(o)).count()
+}
 
-    Ok(())
+// each card is worth 1 point for its first match, doubled for every match after that
+pub fn total_points(card_stack: &[Card]) -> Result<usize> {
+    card_stack
+        .iter()
+        .map(matching_numbers)
+        .filter(|&card_score| card_score > 0)
+        .try_fold(0usize, |total, card_score| {
+            let points = u32::try_from(card_score - 1)
+                .ok()
+                .and_then(|doublings| 1usize.checked_shl(doublings))?;
+            total.checked_add(points)
+        })
+        .ok_or_else(|| AocError::overflow("card points"))
 }
 
-pub fn card_line<'a>(input: &mut &'a str) -> PResult<(HashSet<usize>, Vec<usize>)> {
-    let winners = preceded(card_line_prefix, numbers).parse_next(input)?;
-    let _ = bar_sep.parse_next(input)?;
-    let ours = numbers.parse_next(input)?;
-    let _ = alt((eof, line_ending)).parse_next(input)?;
+// each match wins a copy of the following cards, and copies win copies too
+pub fn total_cards(card_stack: &[Card]) -> Result<usize> {
+    let overflow = || AocError::overflow("card copies");
+    let mut copies = vec![1usize; card_stack.len()];
+
+    for (i, card) in card_stack.iter().enumerate() {
+        let card_score = matching_numbers(card);
+
+        for ix in i + 1..=(i + card_score).min(card_stack.len() - 1) {
+            copies[ix] = copies[ix].checked_add(copies[i]).ok_or_else(overflow)?;
+        }
+    }
 
-    let hs = HashSet::from_iter(winners);
-    let data = (hs, ours);
+    copies
+        .iter()
+        .try_fold(0usize, |total, &count| total.checked_add(count))
+        .ok_or_else(overflow)
+}
 
-    Ok(data)
+pub struct Day4 {
+    card_stack: Vec<Card>,
 }
 
-pub fn parse_stack<'a>(input: &mut &'a str) -> PResult<Vec<(HashSet<usize>, Vec<usize>)>> {
-    repeat(0.., card_line).parse_next(input)
+impl Solution for Day4 {
+    fn parse(input: &Input) -> Result<Self> {
+        let card_stack = parse::run(input.as_str(), parse_stack)?;
+
+        Ok(Day4 { card_stack })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(total_points(&self.card_stack)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(total_cards(&self.card_stack)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
+    use aoc_core::error::AocError;
 
     #[derive(Debug)]
     struct CountData {
@@ -58,14 +103,6 @@ mod tests {
         copies: usize,
     }
 
-    fn load_file() -> String {
-        let mut file = File::open("src/fixtures/day4.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
-
-        file_contents
-    }
-
     #[test]
     fn day4_test2() {
         let start = std::time::Instant::now();
@@ -77,12 +114,12 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
 
-        let card_stack = parse_stack.parse_next(&mut input.as_ref()).unwrap();
+        let card_stack = parse_stack.parse(input).unwrap();
 
         let mut waterfall_count: Vec<CountData> = card_stack
             .iter()
             .map(|(winners, ours)| {
-                let card_score = ours.iter().filter(|o| winners.contains(&o)).count();
+                let card_score = ours.iter().filter(|o| winners.contains(o)).count();
 
                 CountData {
                     card_score,
@@ -112,14 +149,14 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
     #[test]
     fn day4_file2() {
         let start = std::time::Instant::now();
-        let input = load_file();
+        let input = crate::fixture(4).unwrap().into_string();
 
         let card_stack = parse_stack.parse_next(&mut input.as_ref()).unwrap();
 
         let mut waterfall_count: Vec<CountData> = card_stack
             .iter()
             .map(|(winners, ours)| {
-                let card_score = ours.iter().filter(|o| winners.contains(&o)).count();
+                let card_score = ours.iter().filter(|o| winners.contains(o)).count();
 
                 CountData {
                     card_score,
@@ -149,7 +186,7 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
     #[test]
     fn day4_file() {
         let start = std::time::Instant::now();
-        let input = load_file();
+        let input = crate::fixture(4).unwrap().into_string();
 
         let answer = parse_stack.parse_next(&mut input.as_ref()).unwrap();
 
@@ -159,14 +196,14 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
             .fold(0u32, |mut acc, (winners, ours)| {
                 let card_score = ours
                     .iter()
-                    .filter(|o| winners.contains(&o))
+                    .filter(|o| winners.contains(o))
                     .enumerate()
                     // .inspect(|x| println!("inner_iter: {:?}", x))
                     .fold(0u32, |mut acc_inner, (i, _)| {
                         if i == 0 {
                             acc_inner = 1;
                         } else {
-                            acc_inner = acc_inner * 2;
+                            acc_inner *= 2;
                         }
                         acc_inner
                     });
@@ -191,7 +228,7 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
 
-        let answer = parse_stack.parse_next(&mut input.as_ref()).unwrap();
+        let answer = parse_stack.parse(input).unwrap();
 
         let sum = answer
             .iter()
@@ -199,14 +236,14 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
             .fold(0u32, |mut acc, (winners, ours)| {
                 let card_score = ours
                     .iter()
-                    .filter(|o| winners.contains(&o))
+                    .filter(|o| winners.contains(o))
                     .enumerate()
                     .inspect(|x| println!("inner_iter: {:?}", x))
                     .fold(0u32, |mut acc_inner, (i, _)| {
                         if i == 0 {
                             acc_inner = 1;
                         } else {
-                            acc_inner = acc_inner * 2;
+                            acc_inner *= 2;
                         }
                         acc_inner
                     });
@@ -216,7 +253,54 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
             });
 
         assert_eq!(sum, 13u32);
+        assert_eq!(total_points(&answer).unwrap(), 13usize);
+        assert_eq!(total_cards(&answer).unwrap(), 30usize);
 
         println!("Process in: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn card_overflow() {
+        let card = |id, matches: usize| {
+            let numbers: Vec<String> = (1..=matches).map(|n| n.to_string()).collect();
+            let numbers = numbers.join(" ");
+            format!("Card {id}: {numbers} | {numbers}\n")
+        };
+
+        let stack = parse_stack.parse(&card(1, 64)).unwrap();
+        assert_eq!(total_points(&stack).unwrap(), 1 << 63);
+        let stack = parse_stack.parse(&card(1, 65)).unwrap();
+        assert!(matches!(
+            total_points(&stack),
+            Err(AocError::Overflow { .. })
+        ));
+        let stack = parse_stack.parse(&(card(1, 64) + &card(2, 64))).unwrap();
+        assert!(matches!(
+            total_points(&stack),
+            Err(AocError::Overflow { .. })
+        ));
+
+        // every card wins a copy of all the rest, doubling the copies each time
+        let text: String = (1..=66).map(|id| card(id, 66)).collect();
+        let stack = parse_stack.parse(&text).unwrap();
+        assert!(matches!(
+            total_cards(&stack),
+            Err(AocError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_card() {
+        let input = Input::new("Card 1: 41 48 | 83 86\nCard 2: 13 x | 61\n");
+        let err = Day4::parse(&input).err().unwrap();
+
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 12,
+                ..
+            }
+        ));
+    }
 }
@@ -1,28 +1,26 @@
-use winnow::ascii::{digit1, line_ending};
-use winnow::combinator::{eof, opt, repeat};
-use winnow::token::take;
+use winnow::ascii::{multispace0, space1};
+use winnow::combinator::{cut_err, empty, eof, opt, preceded, repeat};
 use winnow::{PResult, Parser};
 
-pub fn file_head_line<'a>(input: &mut &'a str) -> PResult<usize> {
-    let _ = take(1usize).parse_next(input)?;
-    digit1.parse_to().parse_next(input)
-}
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
 
-pub fn file_header<'a>(input: &mut &'a str) -> PResult<Vec<usize>> {
-    let _ = "seeds:".parse_next(input)?;
-    let header = repeat(1.., file_head_line).parse_next(input)?;
-    let _ = take(2usize).parse_next(input)?;
+pub fn file_header(input: &mut &str) -> PResult<Vec<usize>> {
+    let seeds = parse::line(parse::labelled("seeds:", parse::unsigned_list)).parse_next(input)?;
+    parse::blank_lines.parse_next(input)?;
 
-    Ok(header)
+    Ok(seeds)
 }
 
-pub fn map_line_entry<'a>(input: &mut &'a str) -> PResult<Mapping> {
-    let dest_start = digit1.parse_to().parse_next(input)?;
-    let _ = take(1usize).parse_next(input)?;
-    let src_start = digit1.parse_to().parse_next(input)?;
-    let _ = take(1usize).parse_next(input)?;
-    let range = digit1.parse_to().parse_next(input)?;
-    let _ = opt(line_ending).parse_next(input)?;
+pub fn map_line_entry(input: &mut &str) -> PResult<Mapping> {
+    let dest_start = parse::unsigned.parse_next(input)?;
+    let (src_start, range) = cut_err(parse::line((
+        preceded(space1, parse::unsigned),
+        preceded(space1, parse::unsigned),
+    )))
+    .parse_next(input)?;
 
     Ok(Mapping {
         dest_start,
@@ -31,20 +29,19 @@ pub fn map_line_entry<'a>(input: &mut &'a str) -> PResult<Mapping> {
     })
 }
 
-pub fn map_hunk<'a>(header_text: &str, input: &mut &'a str) -> PResult<Vec<Mapping>> {
-    let _ = (header_text, line_ending).parse_next(input)?;
-    let v = repeat(1.., map_line_entry).parse_next(input)?;
-    let _ = line_ending.parse_next(input)?;
+pub fn map_hunk(header_text: &'static str, input: &mut &str) -> PResult<Vec<Mapping>> {
+    parse::line(parse::labelled(header_text, empty)).parse_next(input)?;
+    let v = cut_err(repeat(1.., map_line_entry)).parse_next(input)?;
+    let _ = opt(parse::blank_lines).parse_next(input)?;
 
     Ok(v)
 }
 
-pub fn humidity_loc_map<'a>(input: &mut &'a str) -> PResult<Vec<Mapping>> {
-    let _ = ("humidity-to-location map:", line_ending).parse_next(input)?;
-    repeat(1.., map_line_entry).parse_next(input)
+pub fn humidity_loc_map(input: &mut &str) -> PResult<Vec<Mapping>> {
+    map_hunk("humidity-to-location map:", input)
 }
 
-pub fn parse_mapping_file<'a>(input: &mut &'a str) -> PResult<MappingSet> {
+pub fn parse_mapping_file(input: &mut &str) -> PResult<MappingSet> {
     let seeds = file_header.parse_next(input)?;
 
     let seed_soil_map = map_hunk("seed-to-soil map:", input)?;
@@ -55,7 +52,7 @@ pub fn parse_mapping_file<'a>(input: &mut &'a str) -> PResult<MappingSet> {
     let temp_humidity_map = map_hunk("temperature-to-humidity map:", input)?;
     let humidity_loc_map = humidity_loc_map.parse_next(input)?;
 
-    let _ = eof.parse_next(input)?;
+    let _ = (multispace0, eof).parse_next(input)?;
 
     Ok((
         seeds,
@@ -69,13 +66,21 @@ pub fn parse_mapping_file<'a>(input: &mut &'a str) -> PResult<MappingSet> {
     ))
 }
 
-pub fn get_next_id(map: &Vec<Mapping>, id_link: IdLink) -> IdLink {
+// the id that `map` takes to `id_link`'s id, or the same id if none does
+pub fn get_next_id(map: &[Mapping], id_link: IdLink) -> Result<IdLink> {
     let (id, loc) = id_link;
     let next_range = map
         .iter()
-        .find(|t| id >= t.dest_start && id < (t.dest_start + t.range));
-
-    next_range.map_or((id, loc), |r| (r.src_start + id - r.dest_start, loc))
+        .find(|t| id >= t.dest_start && id - t.dest_start < t.range);
+
+    match next_range {
+        Some(r) => r
+            .src_start
+            .checked_add(id - r.dest_start)
+            .map(|prev| (prev, loc))
+            .ok_or_else(|| AocError::overflow("almanac mapping")),
+        None => Ok((id, loc)),
+    }
 }
 
 pub type IdLink = (usize, usize);
@@ -87,7 +92,7 @@ pub struct Mapping {
     range: usize,
 }
 
-type MappingSet = (
+pub type MappingSet = (
     Vec<usize>,
     Vec<Mapping>,
     Vec<Mapping>,
@@ -98,35 +103,101 @@ type MappingSet = (
     Vec<Mapping>,
 );
 
+fn map_forward(map: &[Mapping], id: usize) -> Result<usize> {
+    match map
+        .iter()
+        .find(|ss| id >= ss.src_start && id - ss.src_start < ss.range)
+    {
+        Some(r) => r
+            .dest_start
+            .checked_add(id - r.src_start)
+            .ok_or_else(|| AocError::overflow("almanac mapping")),
+        None => Ok(id),
+    }
+}
+
+pub fn lowest_seed_location(mapping_set: &MappingSet) -> Result<Option<usize>> {
+    let (seeds, seed_map, soil_map, fert_map, wate_map, ligh_map, temp_map, humi_map) = mapping_set;
+    let maps = [
+        seed_map, soil_map, fert_map, wate_map, ligh_map, temp_map, humi_map,
+    ];
+
+    let locations = seeds
+        .iter()
+        .map(|&seed| maps.iter().try_fold(seed, |id, map| map_forward(map, id)))
+        .collect::<Result<Vec<usize>>>()?;
+
+    Ok(locations.into_iter().min())
+}
+
+// walk locations upwards from 0 and trace each back to a seed, stopping at the
+// first one that lands inside one of the seed ranges
+pub fn lowest_seed_range_location(mapping_set: &MappingSet) -> Result<Option<usize>> {
+    let (seeds, seed_map, soil_map, fert_map, wate_map, ligh_map, temp_map, humi_map) = mapping_set;
+    let maps = [
+        humi_map, temp_map, ligh_map, wate_map, fert_map, soil_map, seed_map,
+    ];
+
+    for loc in 0..usize::MAX {
+        let (seed, _) = maps
+            .iter()
+            .try_fold((loc, loc), |link, map| get_next_id(map, link))?;
+
+        if seeds
+            .chunks_exact(2)
+            .any(|range| seed >= range[0] && seed - range[0] < range[1])
+        {
+            return Ok(Some(loc));
+        }
+    }
+
+    Ok(None)
+}
+
+pub struct Day5 {
+    mapping_set: MappingSet,
+}
+
+impl Solution for Day5 {
+    fn parse(input: &Input) -> Result<Self> {
+        let mapping_set = parse::run(input.as_str(), parse_mapping_file)?;
+
+        Ok(Day5 { mapping_set })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        lowest_seed_location(&self.mapping_set)?
+            .map(Answer::from)
+            .ok_or_else(|| AocError::invalid("Almanac has no seeds"))
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        lowest_seed_range_location(&self.mapping_set)?
+            .map(Answer::from)
+            .ok_or_else(|| AocError::invalid("No location maps back to a seed range"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
-
-    fn load_file() -> String {
-        let mut file = File::open("src/fixtures/day5.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
-        file_contents
-    }
 
     #[test]
     fn day5_file2() {
         let start = std::time::Instant::now();
-        let input = load_file();
+        let input = crate::fixture(5).unwrap().into_string();
 
         let answer = parse_mapping_file.parse_next(&mut input.as_ref()).unwrap();
         let (seeds, seed_map, soil_map, fert_map, wate_map, ligh_map, temp_map, humi_map) = answer;
 
         let min_loc = (0..usize::MAX)
-            .map(|loc| get_next_id(&humi_map, (loc, loc)))
-            .map(|h_link| get_next_id(&temp_map, h_link))
-            .map(|t_link| get_next_id(&ligh_map, t_link))
-            .map(|w_link| get_next_id(&wate_map, w_link))
-            .map(|f_link| get_next_id(&fert_map, f_link))
-            .map(|s_link| get_next_id(&soil_map, s_link))
-            .map(|seed_link| get_next_id(&seed_map, seed_link))
+            .map(|loc| get_next_id(&humi_map, (loc, loc)).unwrap())
+            .map(|h_link| get_next_id(&temp_map, h_link).unwrap())
+            .map(|t_link| get_next_id(&ligh_map, t_link).unwrap())
+            .map(|w_link| get_next_id(&wate_map, w_link).unwrap())
+            .map(|f_link| get_next_id(&fert_map, f_link).unwrap())
+            .map(|s_link| get_next_id(&soil_map, s_link).unwrap())
+            .map(|seed_link| get_next_id(&seed_map, seed_link).unwrap())
             .find(|&seed| {
                 for i in (0..seeds.len()).step_by(2) {
                     let start = seeds[i];
@@ -186,7 +257,7 @@ humidity-to-location map:
 60 56 37
 56 93 4"#;
 
-        let answer = parse_mapping_file.parse_next(&mut input.as_ref()).unwrap();
+        let answer = parse_mapping_file.parse(input).unwrap();
         let (seeds, seed_map, soil_map, fert_map, wate_map, ligh_map, temp_map, humi_map) = answer;
 
         // line: dest-map-start src-map-start range-len
@@ -201,18 +272,18 @@ humidity-to-location map:
 
         let min_loc = (0..usize::MAX)
             // .inspect(|x| println!("Starting loc: {:#?}", x))
-            .map(|loc| get_next_id(&humi_map, (loc, loc)))
-            .map(|h_link: IdLink| get_next_id(&temp_map, h_link))
+            .map(|loc| get_next_id(&humi_map, (loc, loc)).unwrap())
+            .map(|h_link: IdLink| get_next_id(&temp_map, h_link).unwrap())
             // .inspect(|x| println!("After temp map: {:#?}", x))
-            .map(|t_link| get_next_id(&ligh_map, t_link))
+            .map(|t_link| get_next_id(&ligh_map, t_link).unwrap())
             // .inspect(|x| println!("After light map: {:#?}", x))
-            .map(|w_link| get_next_id(&wate_map, w_link))
+            .map(|w_link| get_next_id(&wate_map, w_link).unwrap())
             // .inspect(|x| println!("After water map: {:#?}", x))
-            .map(|f_link| get_next_id(&fert_map, f_link))
+            .map(|f_link| get_next_id(&fert_map, f_link).unwrap())
             // .inspect(|x| println!("After fert map: {:#?}", x))
-            .map(|s_link| get_next_id(&soil_map, s_link))
+            .map(|s_link| get_next_id(&soil_map, s_link).unwrap())
             // .inspect(|x| println!("After soil map: {:#?}", x))
-            .map(|seed_link| get_next_id(&seed_map, seed_link))
+            .map(|seed_link| get_next_id(&seed_map, seed_link).unwrap())
             // .inspect(|x| println!("After seed map: {:#?}", x))
             .find(|&seed| {
                 for i in (0..seeds.len()).step_by(2) {
@@ -238,7 +309,7 @@ humidity-to-location map:
     #[test]
     fn day5_file() {
         let start = std::time::Instant::now();
-        let input = load_file();
+        let input = crate::fixture(5).unwrap().into_string();
 
         let answer = parse_mapping_file.parse_next(&mut input.as_ref()).unwrap();
 
@@ -299,7 +370,7 @@ humidity-to-location map:
 
         let min_seed_location = seeds
             .into_iter()
-            .zip(locations.into_iter())
+            .zip(locations)
             .min_by(|seed_loc1, seed_loc2| seed_loc1.1.cmp(&seed_loc2.1))
             .unwrap();
 
@@ -347,7 +418,7 @@ humidity-to-location map:
 60 56 37
 56 93 4"#;
 
-        let answer = parse_mapping_file.parse_next(&mut input.as_ref()).unwrap();
+        let answer = parse_mapping_file.parse(input).unwrap();
         // line: dest-map-start src-map-start range-len
         //
         // 1. take input_no# and test which range it is within: (src-map-start .. src-map-start + range-len)
@@ -413,13 +484,64 @@ humidity-to-location map:
 
         let min_seed_location = seeds
             .into_iter()
-            .zip(locations.into_iter())
+            .zip(locations)
             .min_by(|seed_loc1, seed_loc2| seed_loc1.1.cmp(&seed_loc2.1))
             .unwrap();
 
         println!("Min Location: {:?}", min_seed_location);
         assert_eq!(min_seed_location.1, 35usize);
 
+        let answer = parse_mapping_file.parse(input).unwrap();
+        assert_eq!(lowest_seed_location(&answer).unwrap(), Some(35usize));
+        assert_eq!(lowest_seed_range_location(&answer).unwrap(), Some(46usize));
+
         println!("Process in: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn almanac_overflow() {
+        let almanac = |seed_soil: String| {
+            let rest = [
+                "soil-to-fertilizer",
+                "fertilizer-to-water",
+                "water-to-light",
+                "light-to-temperature",
+                "temperature-to-humidity",
+                "humidity-to-location",
+            ]
+            .map(|name| format!("{name} map:\n0 0 1\n"))
+            .join("\n");
+            let text = format!("seeds: 1 1\n\nseed-to-soil map:\n{seed_soil}\n\n{rest}");
+            parse_mapping_file.parse(&text).unwrap()
+        };
+
+        let forward = almanac(format!("{} 0 2", usize::MAX));
+        assert!(matches!(
+            lowest_seed_location(&forward),
+            Err(AocError::Overflow { .. })
+        ));
+
+        let backward = almanac(format!("0 {} 2", usize::MAX));
+        assert!(matches!(
+            lowest_seed_range_location(&backward),
+            Err(AocError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_almanac() {
+        let input = Input::new("seeds: 79 14\n\nseed-to-soil map:\n50 98 x\n");
+        let err = Day5::parse(&input).err().unwrap();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 4,
+                column: 7,
+                ..
+            }
+        ));
+
+        let input = Input::new("seeds 79 14\n");
+        assert!(Day5::parse(&input).is_err());
+    }
 }
@@ -1,26 +1,32 @@
 #![allow(unused)]
 
-fn get_winning_wait_times(races: impl Iterator<Item = RaceStat>) -> Vec<Vec<usize>> {
+use winnow::ascii::{digit1, multispace0};
+use winnow::combinator::terminated;
+
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
+
+fn get_winning_wait_times(races: impl IntoIterator<Item = RaceStat>) -> Vec<Vec<usize>> {
     races
-        // .inspect(|x| println!("x: {:?}", x))
+        .into_iter()
         .filter_map(
             |RaceStat {
                  duration,
                  max_distance,
              }| {
                 let wins = (1..duration)
-                    .filter_map(|wait| {
+                    .filter(|&wait| {
                         let speed = wait;
-                        let total_distance = speed * (duration - wait);
-                        if total_distance > max_distance {
-                            return Some(wait);
-                        }
-
-                        None
+                        // a distance too large for usize beats any record
+                        speed
+                            .checked_mul(duration - wait)
+                            .is_none_or(|total_distance| total_distance > max_distance)
                     })
                     .collect::<Vec<usize>>();
 
-                if wins.len() > 0 {
+                if !wins.is_empty() {
                     return Some(wins);
                 }
 
@@ -30,65 +36,105 @@ fn get_winning_wait_times(races: impl Iterator<Item = RaceStat>) -> Vec<Vec<usiz
         .collect::<Vec<Vec<usize>>>()
 }
 
-fn get_race_stats2<'a>(input: &'a str) -> impl Iterator<Item = RaceStat> + 'a {
-    let mut lines = input.lines();
-    let times = lines.next().unwrap().split_whitespace();
-    let durations = lines.next().unwrap().split_whitespace();
-
-    let time = times
-        .zip(durations)
-        .skip(1)
-        // .inspect(|x| println!("x is: {:?}", x))
-        .fold(("".to_owned(), "".to_owned()), |mut acc, pair| {
-            acc.0 = format!("{}{}", acc.0, pair.0);
-            acc.1 = format!("{}{}", acc.1, pair.1);
-
-            acc
-        });
-
-    vec![RaceStat {
-        duration: time.0.parse().expect("Invalid number for duration"),
-        max_distance: time.1.parse().expect("Invalid number for distance"),
-    }]
-    .into_iter()
+// the values after the label on the Time (line 1) or Distance (line 2) row
+fn race_sheet(input: &str) -> Result<(Vec<&str>, Vec<&str>)> {
+    let (times, distances) = parse::run(
+        input,
+        terminated(
+            (
+                parse::line(parse::labelled("Time:", parse::spaced(digit1))),
+                parse::line(parse::labelled("Distance:", parse::spaced(digit1))),
+            ),
+            multispace0,
+        ),
+    )?;
+
+    if times.len() != distances.len() {
+        return Err(AocError::parse(
+            2,
+            1,
+            format!("{} times but {} distances", times.len(), distances.len()),
+        ));
+    }
+
+    Ok((times, distances))
+}
+
+fn sheet_number(value: &str) -> Result<usize> {
+    value
+        .parse()
+        .map_err(|_| AocError::overflow(format!("race value {value}")))
 }
 
-fn get_race_stats<'a>(input: &'a str) -> impl Iterator<Item = RaceStat> + 'a {
-    let mut lines = input.lines();
-    let times = lines.next().unwrap().split_whitespace();
-    let durations = lines.next().unwrap().split_whitespace();
+fn get_race_stats2(input: &str) -> Result<Vec<RaceStat>> {
+    let (times, distances) = race_sheet(input)?;
 
-    times.zip(durations).skip(1).map(|pair| RaceStat {
-        duration: pair.0.parse::<usize>().expect("Invalid number"),
-        max_distance: pair.1.parse::<usize>().expect("Invalid number"),
-    })
+    Ok(vec![RaceStat {
+        duration: sheet_number(&times.concat())?,
+        max_distance: sheet_number(&distances.concat())?,
+    }])
+}
+
+fn get_race_stats(input: &str) -> Result<Vec<RaceStat>> {
+    let (times, distances) = race_sheet(input)?;
+
+    times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, distance)| {
+            Ok(RaceStat {
+                duration: sheet_number(time)?,
+                max_distance: sheet_number(distance)?,
+            })
+        })
+        .collect()
 }
 
 #[derive(Debug)]
-struct RaceStat {
+pub struct RaceStat {
     duration: usize,
     max_distance: usize,
 }
 
+pub fn margin_of_error(races: impl IntoIterator<Item = RaceStat>) -> Result<usize> {
+    get_winning_wait_times(races)
+        .iter()
+        .try_fold(1usize, |acc, win| acc.checked_mul(win.len()))
+        .ok_or_else(|| AocError::overflow("margin of error"))
+}
+
+pub struct Day6 {
+    race_sheet: String,
+}
+
+impl Solution for Day6 {
+    fn parse(input: &Input) -> Result<Self> {
+        race_sheet(input.as_str())?;
+
+        Ok(Day6 {
+            race_sheet: input.as_str().to_owned(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(margin_of_error(get_race_stats(&self.race_sheet)?)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(margin_of_error(get_race_stats2(&self.race_sheet)?)?.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
-
-    fn load_file() -> String {
-        let mut file = File::open("src/fixtures/day6.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
-        file_contents
-    }
 
     #[test]
     fn day6_file2() {
         let start = std::time::Instant::now();
-        let input = load_file();
+        let input = crate::fixture(6).unwrap().into_string();
 
-        let race_stats = get_race_stats2(&input);
+        let race_stats = get_race_stats2(&input).unwrap();
         let winning_wait_times = get_winning_wait_times(race_stats);
         let margin_of_error = winning_wait_times
             .iter()
@@ -105,7 +151,7 @@ mod tests {
         let input = r#"Time:      7  15   30
 Distance:  9  40  200"#;
 
-        let race_stat = get_race_stats2(&input);
+        let race_stat = get_race_stats2(input).unwrap();
         let winning_wait_times = get_winning_wait_times(race_stat);
         // println!("Winning waits: {:?}", &winning_wait_times);
         let margin_of_error = winning_wait_times
@@ -120,9 +166,9 @@ Distance:  9  40  200"#;
     #[test]
     fn day6_file() {
         let start = std::time::Instant::now();
-        let input = load_file();
+        let input = crate::fixture(6).unwrap().into_string();
 
-        let race_stats = get_race_stats(&input);
+        let race_stats = get_race_stats(&input).unwrap();
         let winning_wait_times = get_winning_wait_times(race_stats);
         // println!("Winning waits: {:?}", &winning_wait_times);
         let margin_of_error = winning_wait_times
@@ -140,7 +186,7 @@ Distance:  9  40  200"#;
         let input = r#"Time:      7  15   30
 Distance:  9  40  200"#;
 
-        let race_stats = get_race_stats(&input);
+        let race_stats = get_race_stats(input).unwrap();
         let winning_wait_times = get_winning_wait_times(race_stats);
         // println!("Winning waits: {:?}", &winning_wait_times);
         let margin_of_error = winning_wait_times
@@ -151,4 +197,26 @@ Distance:  9  40  200"#;
 
         println!("Process in: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn invalid_race_sheet() {
+        let err = get_race_stats("Time: 7 x\nDistance: 9 40").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 1,
+                column: 9,
+                ..
+            }
+        ));
+
+        let err = get_race_stats2("Time: 7 15\nDistance: 9").unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+
+        assert!(get_race_stats("Time: 7 15").is_err());
+        assert!(matches!(
+            get_race_stats2("Time: 99999 99999 99999 99999 99999\nDistance: 1 1 1 1 1"),
+            Err(AocError::Overflow { .. })
+        ));
+    }
 }
@@ -9,49 +9,53 @@
 
 #![allow(unused)]
 
-mod lsd_sort;
 use std::collections::HashMap;
 use std::slice;
 
-use lsd_sort::*;
+use winnow::ascii::{space0, space1};
+use winnow::combinator::{delimited, repeat};
+use winnow::token::one_of;
+use winnow::{PResult, Parser};
+
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::lsd_sort;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
 
 pub fn get_total_winnings(
     input: String,
     radix_sort_map: Option<&HashMap<char, char>>,
     use_wildcards: bool,
-) -> usize {
-    let mut hands = parse_file(input);
+) -> Result<usize> {
+    let mut hands = parse_file(input)?;
     identify_hand_types(&mut hands, use_wildcards);
     let mut partitions = paritition_hands_by_type(hands);
-    let total_winnings = radix_sort_partitions(&mut partitions, radix_sort_map);
 
-    total_winnings
+    radix_sort_partitions(&mut partitions, radix_sort_map)
+        .ok_or_else(|| AocError::overflow("total winnings"))
 }
 
 fn radix_sort_partitions(
-    partitions: &mut Vec<Vec<Hand>>,
+    partitions: &mut [Vec<Hand>],
     radix_sort_map: Option<&HashMap<char, char>>,
-) -> usize {
+) -> Option<usize> {
     let mut idx = 1;
-    let total_winnings = partitions.iter_mut().fold(0, |mut acc, mut part| {
+    let mut total_winnings = 0usize;
+
+    for part in partitions.iter_mut() {
         if part.len() > 1 {
-            lsd_sort::sort_hands(&mut part, radix_sort_map);
+            lsd_sort::sort_by_key(part, |hand| &hand.cards, radix_sort_map);
         }
-        let mut i = &mut idx;
 
-        let part_tot_winnings = part.iter_mut().fold(0, move |mut part_tot, mut hand| {
-            hand.rank = *i;
-            part_tot += hand.bet * *i;
-            *i += 1;
-
-            part_tot
-        });
-
-        acc += part_tot_winnings;
-        acc
-    });
+        for hand in part.iter_mut() {
+            hand.rank = idx;
+            total_winnings = total_winnings.checked_add(hand.bet.checked_mul(idx)?)?;
+            idx += 1;
+        }
+    }
 
-    total_winnings
+    Some(total_winnings)
 }
 
 fn paritition_hands_by_type(hands: Vec<Hand>) -> Vec<Vec<Hand>> {
@@ -88,7 +92,7 @@ fn paritition_hands_by_type(hands: Vec<Hand>) -> Vec<Vec<Hand>> {
     })
 }
 
-fn identify_hand_types(hands: &mut Vec<Hand>, use_wildcards: bool) {
+fn identify_hand_types(hands: &mut [Hand], use_wildcards: bool) {
     hands.iter_mut().for_each(|hand| {
         let mut ht: HashMap<char, usize> = HashMap::new();
 
@@ -102,25 +106,28 @@ fn identify_hand_types(hands: &mut Vec<Hand>, use_wildcards: bool) {
     })
 }
 
-fn parse_file(input: String) -> Vec<Hand> {
-    let lines = input
-        .lines()
-        .map(|l| {
-            let record: Vec<String> = l
-                .split_whitespace()
-                .map(|l| l.to_string())
-                .collect::<Vec<String>>();
-
-            Hand {
-                cards: record[0].clone(),
-                hand_type: HandType::Ungraded,
-                bet: record[1].parse().expect("Should be a bet"),
-                rank: 0,
-            }
-        })
-        .collect();
+const CARD_LABELS: &str = "23456789TJQKA";
+
+// "32T3K 765": five cards and a bet
+fn hand(input: &mut &str) -> PResult<Hand> {
+    let cards = repeat::<_, _, (), _, _>(5, one_of(|c: char| CARD_LABELS.contains(c)))
+        .recognize()
+        .context(parse::label("card label"))
+        .parse_next(input)?;
+    let bet = delimited(space1, parse::unsigned, space0)
+        .context(parse::label("bet"))
+        .parse_next(input)?;
+
+    Ok(Hand {
+        cards: cards.to_owned(),
+        hand_type: HandType::Ungraded,
+        bet,
+        rank: 0,
+    })
+}
 
-    lines
+fn parse_file(input: String) -> Result<Vec<Hand>> {
+    parse::run_lines(&input, hand)
 }
 
 #[derive(Debug, Clone)]
@@ -171,7 +178,7 @@ fn convert_card_hash_to_handtype(
             }
         } else if four_of_a_kind == 1 {
             HandType::FiveOfAKind
-        } else if card >= 1 && card < 5 {
+        } else if (1..5).contains(&card) {
             match jacks_number {
                 1 => HandType::OnePair,
                 2 => HandType::ThreeOfAKind,
@@ -227,11 +234,11 @@ pub fn get_card_overrides_part2() -> HashMap<char, char> {
     // so we want normal alphanumerical ASCII ordering by bytecode
     // to change T, Q, and A ordering
     let mut override_sort: HashMap<char, char> = HashMap::new();
-    override_sort.insert('T' as char, 'A' as char);
-    override_sort.insert('A' as char, 'T' as char);
-    override_sort.insert('Q' as char, 'K' as char);
-    override_sort.insert('K' as char, 'L' as char);
-    override_sort.insert('J' as char, '1' as char);
+    override_sort.insert('T', 'A');
+    override_sort.insert('A', 'T');
+    override_sort.insert('Q', 'K');
+    override_sort.insert('K', 'L');
+    override_sort.insert('J', '1');
     // ASCII code for '1' ranks lower than any
     // alpha or other number
     override_sort
@@ -242,35 +249,48 @@ pub fn get_card_overrides_part1() -> HashMap<char, char> {
     // so we want normal alphanumerical ASCII ordering by bytecode
     // to change T, Q, and A ordering
     let mut override_sort: HashMap<char, char> = HashMap::new();
-    override_sort.insert('T' as char, 'A' as char);
-    override_sort.insert('A' as char, 'T' as char);
-    override_sort.insert('Q' as char, 'K' as char);
-    override_sort.insert('K' as char, 'L' as char);
+    override_sort.insert('T', 'A');
+    override_sort.insert('A', 'T');
+    override_sort.insert('Q', 'K');
+    override_sort.insert('K', 'L');
     override_sort
 }
 
-use std::fs::File;
-use std::io::prelude::*;
-pub fn load_file(path: &str) -> String {
-    let mut file = File::open(path).unwrap();
-    let mut file_contents = String::new();
-    let _ = file.read_to_string(&mut file_contents);
-    file_contents
+pub struct Day7 {
+    hands: String,
+}
+
+impl Solution for Day7 {
+    fn parse(input: &Input) -> Result<Self> {
+        parse_file(input.as_str().to_owned())?;
+
+        Ok(Day7 {
+            hands: input.as_str().to_owned(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        let ht = get_card_overrides_part1();
+        Ok(get_total_winnings(self.hands.clone(), Some(&ht), false)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        let ht = get_card_overrides_part2();
+        Ok(get_total_winnings(self.hands.clone(), Some(&ht), true)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
 
     #[test]
     fn day7_file2() {
         let start = std::time::Instant::now();
-        let input = load_file("src/fixtures/day7.txt");
+        let input = crate::fixture(7).unwrap().into_string();
 
         let mut ht = get_card_overrides_part2();
-        let total_winnings = get_total_winnings(input, Some(&ht), true);
+        let total_winnings = get_total_winnings(input, Some(&ht), true).unwrap();
         assert_eq!(total_winnings, 245576185usize);
 
         println!("Process in: {:?}", start.elapsed());
@@ -286,7 +306,7 @@ KTJJT 220
 QQQJA 483"#;
 
         let mut ht = get_card_overrides_part2();
-        let total_winnings = get_total_winnings(input.to_string(), Some(&ht), true);
+        let total_winnings = get_total_winnings(input.to_string(), Some(&ht), true).unwrap();
 
         assert_eq!(total_winnings, 5905usize);
 
@@ -296,10 +316,10 @@ QQQJA 483"#;
     #[test]
     fn day7_file() {
         let start = std::time::Instant::now();
-        let input = load_file("src/fixtures/day7.txt");
+        let input = crate::fixture(7).unwrap().into_string();
 
         let mut ht = get_card_overrides_part1();
-        let total_winnings = get_total_winnings(input, Some(&ht), false);
+        let total_winnings = get_total_winnings(input, Some(&ht), false).unwrap();
         assert_eq!(total_winnings, 248217452usize);
 
         println!("Process in: {:?}", start.elapsed());
@@ -315,10 +335,48 @@ KTJJT 220
 QQQJA 483"#;
 
         let mut ht = get_card_overrides_part1();
-        let total_winnings = get_total_winnings(input.to_string(), Some(&ht), false);
+        let total_winnings = get_total_winnings(input.to_string(), Some(&ht), false).unwrap();
 
         assert_eq!(total_winnings, 6440usize);
 
         println!("Process in: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn invalid_hands() {
+        let ht = get_card_overrides_part1();
+        let winnings = |input: &str| get_total_winnings(input.to_owned(), Some(&ht), false);
+
+        let err = winnings("32T3K 765\nKK6X7 28\n").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 4,
+                ..
+            }
+        ));
+
+        let err = winnings("32T3K 765\nT55J5\n").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 6,
+                ..
+            }
+        ));
+
+        let err = winnings("32T3K seven").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 1,
+                column: 7,
+                ..
+            }
+        ));
+
+        assert!(winnings("32T3 765").is_err());
+    }
 }
@@ -61,35 +61,55 @@
 //
 // Simultaneously start on every node that ends with A. How many steps does it take before you're only on nodes that end with Z?
 
-use core::panic;
 use std::collections::HashMap;
 
+use tracing::{debug, trace};
+use winnow::ascii::multispace0;
+use winnow::combinator::{alt, repeat, separated_pair, terminated};
+use winnow::{PResult, Parser};
+
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
+
+// the node reached from `node` by one left (0) or right (1) step
+fn step<'t>(nodes: &HashMap<&'t str, Nodes<'t>>, node: &str, nav_step: usize) -> Result<&'t str> {
+    let (left, right) = nodes
+        .get(node)
+        .ok_or_else(|| AocError::invalid(format!("Node {node} is not on the map")))?;
+
+    Ok(if nav_step == 0 { left } else { right })
+}
+
+fn ensure_directions(directions: &[usize]) -> Result<()> {
+    if directions.is_empty() {
+        return Err(AocError::invalid("Map has no left/right instructions"));
+    }
+
+    Ok(())
+}
+
 pub fn navigate_graph_alternate_bruteforce(
     directions: &Vec<usize>,
     nodes: &HashMap<&str, Nodes>,
     starts: Vec<&str>,
-) -> usize {
+) -> Result<usize> {
+    ensure_directions(directions)?;
     let mut steps = 0;
 
     let mut next_nodes = starts;
     let mut intermediate: Vec<&str> = vec![];
     'outer: loop {
-        for nav_step in directions {
+        for &nav_step in directions {
             for node in next_nodes.iter() {
-                let child_nodes = nodes.get(node).expect("Must exist");
-                let next_dir_key = match nav_step {
-                    0 => child_nodes.0,
-                    1 => child_nodes.1,
-                    _ => panic!("Won't happen"),
-                };
-
-                intermediate.push(next_dir_key);
+                intermediate.push(step(nodes, node, nav_step)?);
             }
 
             steps += 1;
 
             if intermediate.iter().all(|&n| n.ends_with('Z')) {
-                println!("have all z's: {:?}", &intermediate);
+                debug!(nodes = ?intermediate, steps, "every ghost reached Z");
                 break 'outer;
             }
 
@@ -98,197 +118,179 @@ pub fn navigate_graph_alternate_bruteforce(
         }
     }
 
-    steps
+    Ok(steps)
 }
 
 pub fn navigate_graph(
     directions: &Vec<usize>,
     nodes: &HashMap<&str, Nodes>,
     targets: NavTargets,
-) -> usize {
+) -> Result<usize> {
+    ensure_directions(directions)?;
     let mut steps = 0;
 
-    let mut next_nodes = nodes.get(targets.0).expect("Must have it");
+    let mut next_dir_key = targets.0;
 
-    println!("directions: {:?}", &directions);
-    let mut next_dir_key = "";
+    debug!(directions = directions.len(), "navigating");
 
     'outer: loop {
-        for nav_step in directions {
-            next_dir_key = match nav_step {
-                0 => next_nodes.0,
-                1 => next_nodes.1,
-                _ => panic!("Won't happen"),
-            };
-
+        for &nav_step in directions {
+            next_dir_key = step(nodes, next_dir_key, nav_step)?;
             steps += 1;
-            // println!("next_dir_key: {:?}", &next_dir_key);
-            next_nodes = nodes.get(next_dir_key).expect("Must exist");
-
-            // if next_dir_key == targets.1 {
-            //     break 'outer;
-            // }
         }
         if next_dir_key == targets.1 {
             break 'outer;
         }
         if steps % 100_000 == 0 {
-            println!("Steps: {:?}", &steps);
+            trace!(steps, "still navigating");
         }
     }
 
-    steps
+    Ok(steps)
 }
 
 pub fn navigate_graph_alternate(
     directions: &Vec<usize>,
     nodes: &HashMap<&str, Nodes>,
     starts: Vec<&str>,
-) -> usize {
-    println!("Starts: {:?}", &starts);
+) -> Result<usize> {
+    ensure_directions(directions)?;
+    debug!(?starts, "navigating ghosts");
     let z_found_at: Vec<usize> = starts
         .iter()
         .map(|&n| {
             let mut steps = 0usize;
-            println!("Node: {:?}", n);
+            trace!(start = n, "following ghost");
             let mut link_node = n;
 
             'outer: loop {
-                for nav_step in directions {
-                    let curr_node = nodes.get(link_node).expect("Must exist");
-
+                for &nav_step in directions {
                     if link_node.ends_with('Z') {
                         break 'outer;
                     }
 
-                    link_node = match nav_step {
-                        0 => curr_node.0,
-                        1 => curr_node.1,
-                        _ => panic!("Won't happen"),
-                    };
-
+                    link_node = step(nodes, link_node, nav_step)?;
                     steps += 1;
                 }
             }
 
-            steps
+            Ok(steps)
         })
-        .collect();
+        .collect::<Result<_>>()?;
 
-    println!("Z found at for all: {:?}", &z_found_at);
+    debug!(?z_found_at, "ghost path lengths");
 
-    0
+    // every ghost loops back onto its Z node on the same cycle, so they all
+    // line up on the least common multiple of the individual path lengths
+    z_found_at
+        .into_iter()
+        .try_fold(1, lcm)
+        .ok_or_else(|| AocError::overflow("least common multiple of ghost paths"))
 }
 
-pub fn parse_graph_def<'t>(input: &'t str) -> (Vec<usize>, HashMap<&str, Nodes>, NavTargets) {
-    let mut lines = input.lines();
-    let dirs: Vec<usize> = lines
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| match c {
-            'L' => 0,
-            'R' => 1,
-            _ => panic!("wth"),
-        })
-        .collect();
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
 
-    let mut ht: HashMap<&str, Nodes> = HashMap::new();
-    let mut nav_targets: Vec<&str> = vec![];
-    let mut lines: Vec<&str> = lines
-        .skip(1) // blank line
-        .collect();
-    lines.sort();
-    lines.iter().for_each(|&line| {
-        let mut splitter = line.split('=');
-        let node_name = splitter.next().unwrap().trim();
-        let node_dests = splitter.next().unwrap().trim();
+fn lcm(a: usize, b: usize) -> Option<usize> {
+    (a / gcd(a, b)).checked_mul(b)
+}
 
-        let mut node_dest_splitter = node_dests.split(',');
-        let left = node_dest_splitter.next().unwrap().trim_matches('(').trim();
-        let right = node_dest_splitter.next().unwrap().trim_matches(')').trim();
+fn directions(input: &mut &str) -> PResult<Vec<usize>> {
+    repeat(1.., alt(('L'.value(0), 'R'.value(1))))
+        .context(parse::label("direction"))
+        .context(parse::expected("L or R"))
+        .parse_next(input)
+}
 
-        // dbg!((left, right));
+// directions on the first line, then a blank line, then one "AAA = (BBB, CCC)"
+// node per line
+fn parse_map(input: &str) -> Result<(Vec<usize>, Vec<Node<'_>>)> {
+    parse::run(
+        input,
+        terminated(
+            separated_pair(
+                parse::line(directions),
+                parse::blank_lines,
+                parse::lines(parse::key_pair),
+            ),
+            multispace0,
+        ),
+    )
+}
 
-        nav_targets.push(node_name);
-        let _ = ht.insert(node_name, (left, right));
-    });
+pub fn parse_graph_def(input: &str) -> Result<(Vec<usize>, Network<'_>, NavTargets<'_>)> {
+    let (dirs, mut nodes) = parse_map(input)?;
+    nodes.sort();
 
-    let start = nav_targets[0];
-    let end = nav_targets[nav_targets.len() - 1];
+    let (Some(&(start, _)), Some(&(end, _))) = (nodes.first(), nodes.last()) else {
+        return Err(AocError::parse(3, 1, "Map has no nodes"));
+    };
 
-    (dirs, ht, (start, end))
+    Ok((dirs, nodes.into_iter().collect(), (start, end)))
 }
 
-pub fn parse_graph_def_alternate<'t>(
-    input: &'t str,
-) -> (Vec<usize>, HashMap<&str, Nodes>, Vec<&str>) {
-    let mut lines = input.lines();
-    let dirs: Vec<usize> = lines
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| match c {
-            'L' => 0,
-            'R' => 1,
-            _ => panic!("wth"),
-        })
-        .collect();
-
-    let mut ht: HashMap<&str, Nodes> = HashMap::new();
-    let mut starts: Vec<&str> = vec![];
+pub fn parse_graph_def_alternate(input: &str) -> Result<(Vec<usize>, Network<'_>, Vec<&str>)> {
+    let (dirs, nodes) = parse_map(input)?;
 
-    let lines: Vec<&str> = lines
-        .skip(1) // blank line
+    let starts = nodes
+        .iter()
+        .map(|&(node_name, _)| node_name)
+        .filter(|node_name| node_name.ends_with('A'))
         .collect();
-    // lines.sort();
-    lines.iter().for_each(|&line| {
-        let mut splitter = line.split('=');
-        let node_name = splitter.next().unwrap().trim();
-        let node_dests = splitter.next().unwrap().trim();
-
-        let mut node_dest_splitter = node_dests.split(',');
-        let left = node_dest_splitter.next().unwrap().trim_matches('(').trim();
-        let right = node_dest_splitter.next().unwrap().trim_matches(')').trim();
-
-        if node_name.ends_with('A') {
-            starts.push(node_name);
-        }
 
-        let _ = ht.insert(node_name, (left, right));
-    });
-
-    (dirs, ht, starts)
+    Ok((dirs, nodes.into_iter().collect(), starts))
 }
 
 pub type NavTargets<'t> = (&'t str, &'t str);
 pub type Nodes<'t> = (&'t str, &'t str);
+pub type Node<'t> = (&'t str, Nodes<'t>);
+pub type Network<'t> = HashMap<&'t str, Nodes<'t>>;
 // pub type Nodes = (usize, usize);
 
+pub struct Day8 {
+    network: String,
+}
+
+impl Solution for Day8 {
+    fn parse(input: &Input) -> Result<Self> {
+        parse_graph_def(input.as_str())?;
+
+        Ok(Day8 {
+            network: input.as_str().to_owned(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        let (directions, nodes, targets) = parse_graph_def(&self.network)?;
+        Ok(navigate_graph(&directions, &nodes, targets)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        let (directions, nodes, starts) = parse_graph_def_alternate(&self.network)?;
+        Ok(navigate_graph_alternate(&directions, &nodes, starts)?.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
-
-    fn load_file() -> String {
-        let mut file = File::open("src/fixtures/day8.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
-        file_contents
-    }
 
     #[test]
     fn day8_file3() {
         let start = std::time::Instant::now();
 
-        let input = load_file();
-        let (directions, nodes, targets) = parse_graph_def_alternate(&input);
+        let input = crate::fixture(8).unwrap().into_string();
+        let (directions, nodes, targets) = parse_graph_def_alternate(&input).unwrap();
         println!(
             "dir: {:?}, nodes: {:?}, targets: {:?}",
             &directions, &nodes, &targets
         );
-        let step_count = navigate_graph_alternate_bruteforce(&directions, &nodes, targets);
+        let step_count = navigate_graph_alternate_bruteforce(&directions, &nodes, targets).unwrap();
 
         assert_eq!(step_count, 19631usize);
         println!("Process in: {:?}", start.elapsed());
@@ -309,12 +311,12 @@ mod tests {
 22Z = (22B, 22B)
 XXX = (XXX, XXX)"#;
 
-        let (directions, nodes, targets) = parse_graph_def_alternate(&input);
+        let (directions, nodes, targets) = parse_graph_def_alternate(input).unwrap();
         println!(
             "dir: {:?}, nodes: {:?}, targets: {:?}",
             &directions, &nodes, &targets
         );
-        let step_count = navigate_graph_alternate(&directions, &nodes, targets);
+        let step_count = navigate_graph_alternate(&directions, &nodes, targets).unwrap();
         assert_eq!(step_count, 6usize);
 
         println!("Process in: {:?}", start.elapsed());
@@ -324,9 +326,9 @@ XXX = (XXX, XXX)"#;
     fn day8_file() {
         let start = std::time::Instant::now();
 
-        let input = load_file();
-        let (directions, nodes, targets) = parse_graph_def(&input);
-        let step_count = navigate_graph(&directions, &nodes, targets);
+        let input = crate::fixture(8).unwrap().into_string();
+        let (directions, nodes, targets) = parse_graph_def(&input).unwrap();
+        let step_count = navigate_graph(&directions, &nodes, targets).unwrap();
 
         assert_eq!(step_count, 19631usize);
         println!("Process in: {:?}", start.elapsed());
@@ -342,8 +344,8 @@ AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)"#;
 
-        let (directions, nodes, targets) = parse_graph_def(input);
-        let step_count = navigate_graph(&directions, &nodes, targets);
+        let (directions, nodes, targets) = parse_graph_def(input).unwrap();
+        let step_count = navigate_graph(&directions, &nodes, targets).unwrap();
 
         assert_eq!(step_count, 6usize);
 
@@ -364,8 +366,8 @@ EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)"#;
 
-        let (directions, nodes, targets) = parse_graph_def(input);
-        let step_count = navigate_graph(&directions, &nodes, targets);
+        let (directions, nodes, targets) = parse_graph_def(input).unwrap();
+        let step_count = navigate_graph(&directions, &nodes, targets).unwrap();
 
         assert_eq!(step_count, 2usize);
 
@@ -374,7 +376,8 @@ ZZZ = (ZZZ, ZZZ)"#;
 
     #[test]
     fn day8_other2() {
-        let input = include_bytes!("./fixtures/day8.txt");
+        let input = crate::fixture(8).unwrap();
+        let input = input.as_bytes();
         let split = input.iter().position(|&c| c == b'\n').unwrap();
 
         let mut map = [0u32; 0b11001_11001_11001 + 1];
@@ -407,7 +410,8 @@ ZZZ = (ZZZ, ZZZ)"#;
     #[test]
     fn day8_other1() {
         // use std::str;
-        let input = include_bytes!("./fixtures/day8.txt");
+        let input = crate::fixture(8).unwrap();
+        let input = input.as_bytes();
         let split = input.iter().position(|&c| c == b'\n').unwrap();
 
         let mut map = [0u32; 0b11001_11001_11001 + 1];
@@ -416,7 +420,7 @@ ZZZ = (ZZZ, ZZZ)"#;
         };
         input[split + 2..].split(|&c| c == b'\n').for_each(|node| {
             // println!("node: {:?}", str::from_utf8(node));
-            if node.len() > 0usize {
+            if !node.is_empty() {
                 map[enc(&node[0..3]) as usize] = enc(&node[7..10]) | enc(&node[12..15]) << 16;
             }
         });
@@ -437,4 +441,45 @@ ZZZ = (ZZZ, ZZZ)"#;
             + 1;
         assert_eq!(step_count, 19631usize);
     }
+
+    #[test]
+    fn invalid_maps() {
+        let err = parse_graph_def("LXR\n\nAAA = (AAA, AAA)").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 1,
+                column: 2,
+                ..
+            }
+        ));
+
+        let err = parse_graph_def("LR\n\nAAA = (BBB, BBB)\nBBB (AAA, ZZZ)").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 4,
+                column: 5,
+                ..
+            }
+        ));
+
+        let err = parse_graph_def("LR\n\nAAA = BBB, BBB").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 3,
+                column: 7,
+                ..
+            }
+        ));
+
+        assert!(parse_graph_def("LR\n").is_err());
+
+        let (directions, nodes, targets) = parse_graph_def("L\n\nAAA = (BBB, BBB)").unwrap();
+        assert!(matches!(
+            navigate_graph(&directions, &nodes, targets),
+            Err(AocError::InvalidState { .. })
+        ));
+    }
 }
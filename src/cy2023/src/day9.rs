@@ -65,9 +65,15 @@ If you find the next value for each history in this example and add them togethe
 Analyze your OASIS report and extrapolate the next value for each history. What is the sum of these extrapolated values?
 */
 
-use anyhow::{anyhow, Context, Result};
 use std::str::FromStr;
 
+use winnow::Parser;
+
+use aoc_core::error::{AocError, Result};
+use aoc_core::input::Input;
+use aoc_core::parse;
+use aoc_core::solution::{Answer, Solution};
+
 struct Measurement(Vec<isize>);
 
 impl Measurement {
@@ -75,113 +81,127 @@ impl Measurement {
         Self(values)
     }
 
+    // an empty difference sequence is all zeros
     fn last(&self) -> isize {
-        *self.0.last().unwrap()
+        self.0.last().copied().unwrap_or(0)
     }
 
     fn first(&self) -> isize {
-        *self.0.first().unwrap()
+        self.0.first().copied().unwrap_or(0)
     }
 
-    fn reduce_all(&self) -> Vec<Measurement> {
-        std::iter::successors(Some(self.reduce_measurement()), |next| {
-            (!next.is_zero_measurement()).then(|| next.reduce_measurement())
-        })
-        .collect()
+    fn reduce_all(&self) -> Option<Vec<Measurement>> {
+        let mut reductions = vec![];
+        let mut next = self.reduce_measurement()?;
+
+        while !next.is_zero_measurement() {
+            let reduced = next.reduce_measurement()?;
+            reductions.push(next);
+            next = reduced;
+        }
+        reductions.push(next);
+
+        Some(reductions)
     }
 
-    fn reduce_measurement(&self) -> Measurement {
-        Measurement::new(
+    fn reduce_measurement(&self) -> Option<Measurement> {
+        Some(Measurement::new(
             self.0
                 .iter()
                 .zip(self.0.iter().skip(1))
-                .map(|(a, b)| b - a)
-                .collect(),
-        )
+                .map(|(a, b)| b.checked_sub(*a))
+                .collect::<Option<_>>()?,
+        ))
     }
 
     fn is_zero_measurement(&self) -> bool {
         self.0.iter().all(|&n| n == 0)
     }
 
-    fn next(&self) -> isize {
-        self.last()
-            + self
-                .reduce_all()
-                .iter()
-                .rfold(0isize, |acc, mem| acc + mem.last())
+    fn next(&self) -> Option<isize> {
+        self.reduce_all()?
+            .iter()
+            .try_rfold(0isize, |acc, mem| acc.checked_add(mem.last()))?
+            .checked_add(self.last())
     }
 
-    fn prev(&self) -> isize {
-        self.first()
-            - self
-                .reduce_all()
-                .iter()
-                .rfold(0isize, |acc, mem| mem.first() - acc)
+    fn prev(&self) -> Option<isize> {
+        let below = self
+            .reduce_all()?
+            .iter()
+            .try_rfold(0isize, |acc, mem| mem.first().checked_sub(acc))?;
+
+        self.first().checked_sub(below)
     }
 }
 
 impl FromStr for Measurement {
-    type Err = anyhow::Error;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self> {
-        Ok(Self::new(
-            s.split_ascii_whitespace()
-                .map(|s| {
-                    s.parse::<isize>()
-                        .with_context(|| anyhow!("Could not parse number"))
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        ))
+        parse::run(s, parse::signed_list.map(Self::new))
     }
 }
 
 pub struct Report(Vec<Measurement>);
 
 impl Report {
-    pub fn total_next(&self) -> isize {
-        self.0.iter().map(Measurement::next).sum()
+    pub fn total_next(&self) -> Result<isize> {
+        self.0
+            .iter()
+            .try_fold(0isize, |acc, m| acc.checked_add(m.next()?))
+            .ok_or_else(|| AocError::overflow("extrapolating next values"))
     }
 
-    pub fn total_prev(&self) -> isize {
-        self.0.iter().map(Measurement::prev).sum()
+    pub fn total_prev(&self) -> Result<isize> {
+        self.0
+            .iter()
+            .try_fold(0isize, |acc, m| acc.checked_add(m.prev()?))
+            .ok_or_else(|| AocError::overflow("extrapolating previous values"))
     }
 }
 
 impl FromStr for Report {
-    type Err = anyhow::Error;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self> {
-        Ok(Self(
-            s.lines()
-                .map(|line| {
-                    line.parse::<Measurement>()
-                        .with_context(|| anyhow!("Invalid measurement"))
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        ))
+        Ok(Self(parse::run_lines(
+            s,
+            parse::signed_list.map(Measurement::new),
+        )?))
+    }
+}
+
+pub struct Day9 {
+    report: Report,
+}
+
+impl Solution for Day9 {
+    fn parse(input: &Input) -> Result<Self> {
+        Ok(Day9 {
+            report: input.as_str().parse()?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(self.report.total_next()?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(self.report.total_prev()?.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::prelude::*;
-
-    fn load_file() -> String {
-        let mut file = File::open("src/fixtures/day9.txt").unwrap();
-        let mut file_contents = String::new();
-        let _ = file.read_to_string(&mut file_contents);
-        file_contents
-    }
 
     #[test]
     fn day9_part2_file() {
         let start = std::time::Instant::now();
-        let sample = load_file();
+        let sample = crate::fixture(9).unwrap().into_string();
         let report = sample.parse::<Report>().unwrap();
-        let total_next_numbers = report.total_prev();
+        let total_next_numbers = report.total_prev().unwrap();
 
         assert_eq!(total_next_numbers, 1124);
         // 1923760368 to high
@@ -198,7 +218,7 @@ mod tests {
         10 13 16 21 30 45"#;
 
         let report = sample.parse::<Report>().unwrap();
-        let total_next_numbers = report.total_prev();
+        let total_next_numbers = report.total_prev().unwrap();
 
         assert_eq!(total_next_numbers, 2);
 
@@ -208,9 +228,9 @@ mod tests {
     #[test]
     fn day9_part1_file() {
         let start = std::time::Instant::now();
-        let sample = load_file();
+        let sample = crate::fixture(9).unwrap().into_string();
         let report = sample.parse::<Report>().unwrap();
-        let total_next_numbers = report.total_next();
+        let total_next_numbers = report.total_next().unwrap();
 
         assert_eq!(total_next_numbers, 1921197370);
         // 1923760368 to high
@@ -227,11 +247,38 @@ mod tests {
         10 13 16 21 30 45"#;
 
         let report = sample.parse::<Report>().unwrap();
-        let total_next_numbers = report.total_next();
+        let total_next_numbers = report.total_next().unwrap();
 
         assert_eq!(total_next_numbers, 114);
 
         // println!("sample_nums: {:?}", &next_numbers);
         println!("Elapsed time: {:?}", start.elapsed());
     }
+
+    #[test]
+    fn invalid_reports() {
+        let err = "0 3 6\n1 x 3".parse::<Report>().err().unwrap();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 3,
+                ..
+            }
+        ));
+
+        assert!("0 3 6\n\n1 2 3".parse::<Report>().is_err());
+
+        let report = "7".parse::<Report>().unwrap();
+        assert_eq!(report.total_next().unwrap(), 7);
+        assert_eq!(report.total_prev().unwrap(), 7);
+
+        let report = format!("{} {}", isize::MIN, isize::MAX)
+            .parse::<Report>()
+            .unwrap();
+        assert!(matches!(
+            report.total_next(),
+            Err(AocError::Overflow { .. })
+        ));
+    }
 }
@@ -11,3 +11,84 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+
+use std::path::PathBuf;
+
+use aoc_core::error::Result;
+use aoc_core::input::Input;
+use aoc_core::solution::Registry;
+
+pub const YEAR: u16 = 2023;
+
+pub fn source_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+pub fn fixture_path(day: u8) -> PathBuf {
+    source_dir().join("fixtures").join(format!("day{day}.txt"))
+}
+
+pub fn fixture(day: u8) -> Result<Input> {
+    Input::from_path(fixture_path(day))
+}
+
+pub fn answers_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+}
+
+pub fn register(registry: &mut Registry) {
+    registry
+        .register::<day1::Day1>(YEAR, 1, "Trebuchet?!")
+        .register::<day2::Day2>(YEAR, 2, "Cube Conundrum")
+        .register::<day3::Day3>(YEAR, 3, "Gear Ratios")
+        .register::<day4::Day4>(YEAR, 4, "Scratchcards")
+        .register::<day5::Day5>(YEAR, 5, "If You Give A Seed A Fertilizer")
+        .register::<day6::Day6>(YEAR, 6, "Wait For It")
+        .register::<day7::Day7>(YEAR, 7, "Camel Cards")
+        .register::<day8::Day8>(YEAR, 8, "Haunted Wasteland")
+        .register::<day9::Day9>(YEAR, 9, "Mirage Maintenance")
+        .register::<day10::Day10>(YEAR, 10, "Pipe Maze")
+        .register::<day11::Day11>(YEAR, 11, "Cosmic Expansion")
+        .register::<day12::Day12>(YEAR, 12, "Hot Springs")
+        .register::<day13::Day13>(YEAR, 13, "Point of Incidence");
+}
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    register(&mut registry);
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_has_every_day() {
+        let registry = registry();
+        let days: Vec<u8> = registry.year(YEAR).map(|s| s.day).collect();
+
+        assert_eq!(days[..13], (1..=13).collect::<Vec<u8>>());
+        assert!(days.iter().all(|&day| fixture_path(day).exists()));
+    }
+
+    #[test]
+    fn loads_fixtures() {
+        assert_eq!(fixture(9).unwrap().lines().count(), 200);
+        assert!(fixture(0).is_err());
+    }
+
+    #[test]
+    fn registry_solves_day1() {
+        let start = std::time::Instant::now();
+        let input = fixture(1).unwrap();
+
+        let solver = registry().get(YEAR, 1).copied().unwrap();
+        let (part1, part2) = solver.solve(&input).unwrap();
+
+        assert_eq!(part1.to_string(), "55130");
+        assert_eq!(part2.to_string(), "54985");
+
+        println!("Process in: {:?}", start.elapsed());
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use aoc_core::error::Result;
+use aoc_core::input::Input;
+use aoc_core::solution::Registry;
+
+pub const YEAR: u16 = 2024;
+
+pub fn source_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+pub fn fixture_path(day: u8) -> PathBuf {
+    source_dir().join("fixtures").join(format!("day{day}.txt"))
+}
+
+pub fn fixture(day: u8) -> Result<Input> {
+    Input::from_path(fixture_path(day))
+}
+
+pub fn answers_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+}
+
+// days are added here by `main new 2024 <day>`
+pub fn register(_registry: &mut Registry) {}
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    register(&mut registry);
+    registry
+}
@@ -0,0 +1,62 @@
+use anyhow::{Context, Result};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+// Solvers log through `tracing` with one target per day (`cy2023::day8`, ...).
+// Nothing is shown unless asked for: each `-v` raises the level by one step,
+// and `--log` (or RUST_LOG) takes full filter directives such as
+// `cy2023::day12=trace`.
+pub fn filter(verbose: u8, directives: Option<&str>) -> Result<EnvFilter> {
+    let level = match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+
+    let directives = match directives {
+        Some(directives) => directives.to_owned(),
+        None => std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default(),
+    };
+
+    EnvFilter::builder()
+        .with_default_directive(level.into())
+        .parse(&directives)
+        .with_context(|| format!("Invalid log filter '{directives}'"))
+}
+
+// logs go to stderr so answers on stdout stay clean
+pub fn init(verbose: u8, directives: Option<&str>) -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(filter(verbose, directives)?)
+        .with_writer(std::io::stderr)
+        .init();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_levels() {
+        assert_eq!(
+            filter(0, Some("")).unwrap().max_level_hint(),
+            Some(LevelFilter::WARN)
+        );
+        assert_eq!(
+            filter(2, Some("")).unwrap().max_level_hint(),
+            Some(LevelFilter::DEBUG)
+        );
+        assert_eq!(
+            filter(9, Some("")).unwrap().max_level_hint(),
+            Some(LevelFilter::TRACE)
+        );
+
+        let per_day = filter(0, Some("cy2023::day8=trace")).unwrap();
+        assert_eq!(per_day.max_level_hint(), Some(LevelFilter::TRACE));
+
+        assert!(filter(0, Some("cy2023::day8=loud")).is_err());
+    }
+}
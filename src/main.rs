@@ -1,22 +1,118 @@
-// use cy2023::{get_card_overrides_part1, get_card_overrides_part2, get_total_winnings, load_file};
-use cy2023::day12::day12_part2_preunfold;
+use std::path::PathBuf;
 
-fn main() {
-    day12_part2_preunfold();
-    // let start = std::time::Instant::now();
-    // let input = load_file("src/cy2023/src/fixtures/day7.txt");
-    //
-    // let ht = get_card_overrides_part2();
-    // let total_winnings = get_total_winnings(input, Some(&ht), true);
-    // assert_eq!(total_winnings, 245576185usize);
-    //
-    // println!("Process in: {:?}", start.elapsed());
-    //
-    // let input2 = load_file("src/cy2023/src/fixtures/day7_2.txt");
-    //
-    // let ht2 = get_card_overrides_part1();
-    // let total_winnings = get_total_winnings(input2, Some(&ht2), false);
-    // assert_eq!(total_winnings, 248217452usize);
-    //
-    // println!("Process in: {:?}", start.elapsed());
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod bench;
+mod logging;
+mod manifest;
+mod runner;
+mod scaffold;
+
+use bench::BenchOptions;
+use runner::InputSource;
+
+#[derive(Parser)]
+#[command(about = "Advent of Code puzzle runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Show solver diagnostics on stderr (-v info, -vv debug, -vvv trace)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Log filter directives, e.g. `cy2023::day8=trace` (overrides RUST_LOG)
+    #[arg(long, global = true)]
+    log: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one day's solver
+    Run {
+        year: u16,
+        day: u8,
+        /// Only run this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Puzzle input file, or `-` for stdin (defaults to the day's fixture)
+        #[arg(long)]
+        input: Option<String>,
+    },
+    /// Run every registered solver against its fixture
+    RunAll {
+        #[arg(long)]
+        year: Option<u16>,
+    },
+    /// Check every registered solver against the known answers
+    Verify {
+        #[arg(long)]
+        year: Option<u16>,
+        #[arg(long)]
+        day: Option<u8>,
+    },
+    /// Time the parse, part 1 and part 2 phases over repeated runs
+    Bench {
+        #[arg(long)]
+        year: Option<u16>,
+        #[arg(long)]
+        day: Option<u8>,
+        /// Number of timed runs per solver
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+        /// Untimed runs before sampling starts
+        #[arg(long, default_value_t = 1)]
+        warmup: usize,
+        /// Flag phases whose median is this many percent slower than the last recorded run
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+        /// History file results are compared against and appended to
+        #[arg(long)]
+        history: Option<PathBuf>,
+    },
+    /// List the registered solvers
+    List,
+    /// Generate a new day module, fixture and registration
+    New {
+        year: u16,
+        day: u8,
+        /// Puzzle title used in the header comment and the registry
+        #[arg(long)]
+        title: Option<String>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    logging::init(cli.verbose, cli.log.as_deref())?;
+
+    match cli.command {
+        Command::Run {
+            year,
+            day,
+            part,
+            input,
+        } => runner::run(year, day, part, InputSource::from_arg(input)),
+        Command::RunAll { year } => runner::run_all(year),
+        Command::Verify { year, day } => runner::verify(year, day),
+        Command::Bench {
+            year,
+            day,
+            iterations,
+            warmup,
+            threshold,
+            history,
+        } => bench::bench(BenchOptions {
+            year,
+            day,
+            iterations,
+            warmup,
+            threshold,
+            history: history.unwrap_or_else(bench::default_history_path),
+        }),
+        Command::List => {
+            runner::list();
+            Ok(())
+        }
+        Command::New { year, day, title } => scaffold::new_day(year, day, title),
+    }
 }
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedAnswer {
+    pub day: u8,
+    pub part: u8,
+    pub input: PathBuf,
+    pub expected: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    pub year: u16,
+    #[serde(default)]
+    pub answers: Vec<ExpectedAnswer>,
+    // directory the manifest was loaded from; `input` paths are relative to it
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+impl Manifest {
+    pub fn parse(contents: &str, root: &Path) -> Result<Self> {
+        let mut manifest: Manifest = toml::from_str(contents)?;
+        manifest.root = root.to_owned();
+
+        for answer in manifest.answers.iter() {
+            if !(1..=2).contains(&answer.part) {
+                bail!(
+                    "{} day {} has an answer for part {}",
+                    manifest.year,
+                    answer.day,
+                    answer.part
+                );
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read answer manifest {}", path.display()))?;
+        let root = path.parent().unwrap_or(Path::new("."));
+
+        Manifest::parse(&contents, root)
+            .with_context(|| format!("Invalid answer manifest {}", path.display()))
+    }
+
+    pub fn input_path(&self, answer: &ExpectedAnswer) -> PathBuf {
+        self.root.join(&answer.input)
+    }
+
+    pub fn answers_for(&self, day: u8, part: u8) -> impl Iterator<Item = &ExpectedAnswer> {
+        self.answers
+            .iter()
+            .filter(move |a| a.day == day && a.part == part)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_parse() {
+        let manifest = Manifest::parse(
+            r#"year = 2023
+
+[[answers]]
+day = 9
+part = 2
+input = "src/fixtures/day9.txt"
+expected = "1124"
+"#,
+            Path::new("/tmp/cy2023"),
+        )
+        .unwrap();
+
+        assert_eq!(manifest.year, 2023);
+        assert_eq!(manifest.answers_for(9, 2).count(), 1);
+        assert_eq!(manifest.answers_for(9, 1).count(), 0);
+
+        let answer = &manifest.answers[0];
+        assert_eq!(answer.expected, "1124");
+        assert_eq!(
+            manifest.input_path(answer),
+            PathBuf::from("/tmp/cy2023/src/fixtures/day9.txt")
+        );
+    }
+
+    #[test]
+    fn manifest_rejects_unknown_part() {
+        let manifest = Manifest::parse(
+            r#"year = 2023
+
+[[answers]]
+day = 1
+part = 3
+input = "day1.txt"
+expected = "1"
+"#,
+            Path::new("."),
+        );
+
+        assert!(manifest.is_err());
+    }
+
+    #[test]
+    fn manifest_covers_registry() {
+        let manifest = Manifest::load(&cy2023::answers_path()).unwrap();
+
+        for solver in crate::runner::registry().year(manifest.year) {
+            for part in 1..=2 {
+                let answers: Vec<&ExpectedAnswer> =
+                    manifest.answers_for(solver.day, part).collect();
+                assert!(
+                    !answers.is_empty(),
+                    "no answer for day {} part {part}",
+                    solver.day
+                );
+
+                for answer in answers {
+                    assert!(manifest.input_path(answer).exists());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,575 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Error, Result};
+use aoc_core::input::Input;
+use aoc_core::solution::{Answer, Registry, Solver};
+
+use crate::manifest::Manifest;
+
+pub enum InputSource {
+    Fixture,
+    Path(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    pub fn from_arg(arg: Option<String>) -> Self {
+        match arg.as_deref() {
+            None => InputSource::Fixture,
+            Some("-") => InputSource::Stdin,
+            Some(path) => InputSource::Path(PathBuf::from(path)),
+        }
+    }
+}
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    cy2023::register(&mut registry);
+    cy2024::register(&mut registry);
+    registry
+}
+
+pub fn source_dir(year: u16) -> Result<PathBuf> {
+    match year {
+        cy2023::YEAR => Ok(cy2023::source_dir()),
+        cy2024::YEAR => Ok(cy2024::source_dir()),
+        _ => bail!("No solver crate for year {year}"),
+    }
+}
+
+pub fn fixture_path(year: u16, day: u8) -> Result<PathBuf> {
+    match year {
+        cy2023::YEAR => Ok(cy2023::fixture_path(day)),
+        cy2024::YEAR => Ok(cy2024::fixture_path(day)),
+        _ => bail!("No fixtures for year {year}"),
+    }
+}
+
+pub fn answers_path(year: u16) -> Result<PathBuf> {
+    match year {
+        cy2023::YEAR => Ok(cy2023::answers_path()),
+        cy2024::YEAR => Ok(cy2024::answers_path()),
+        _ => bail!("No answer manifest for year {year}"),
+    }
+}
+
+pub fn read_input(solver: &Solver, source: &InputSource) -> Result<Input> {
+    match source {
+        InputSource::Fixture => Ok(Input::from_path(fixture_path(solver.year, solver.day)?)?),
+        InputSource::Path(path) => Ok(Input::from_path(path)?),
+        InputSource::Stdin => Ok(Input::from_stdin()?),
+    }
+}
+
+pub struct PartResult {
+    pub part: u8,
+    pub answer: Result<Answer>,
+    pub elapsed: Duration,
+}
+
+pub struct RunResult {
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+pub fn run_solver(solver: &Solver, input: &Input, parts: &[u8]) -> Result<RunResult> {
+    let start = Instant::now();
+    let solution = solver
+        .parse(input)
+        .with_context(|| format!("{} day {} failed to parse", solver.year, solver.day))?;
+    let parse_elapsed = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                1 => solution.part1().map_err(Error::from),
+                2 => solution.part2().map_err(Error::from),
+                _ => Err(anyhow!("There is no part {part}")),
+            };
+
+            PartResult {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+
+    Ok(RunResult {
+        parse_elapsed,
+        parts,
+    })
+}
+
+pub fn print_run(solver: &Solver, result: &RunResult) {
+    println!(
+        "{} day {:>2}: {} (parsed in {:?})",
+        solver.year, solver.day, solver.title, result.parse_elapsed
+    );
+
+    for part in result.parts.iter() {
+        match &part.answer {
+            Ok(answer) => println!("  part {}: {answer} ({:?})", part.part, part.elapsed),
+            Err(e) => println!("  part {}: error: {e:#}", part.part),
+        }
+    }
+}
+
+pub fn run(year: u16, day: u8, part: Option<u8>, source: InputSource) -> Result<()> {
+    let registry = registry();
+    let solver = registry
+        .get(year, day)
+        .ok_or_else(|| anyhow!("No solver registered for {year} day {day}"))?;
+
+    let input = read_input(solver, &source)?;
+    let parts = part.map_or(vec![1, 2], |p| vec![p]);
+    let result = run_solver(solver, &input, &parts)?;
+
+    print_run(solver, &result);
+
+    match result.parts.into_iter().find_map(|p| p.answer.err()) {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+pub fn run_all(year: Option<u16>) -> Result<()> {
+    let registry = registry();
+    let start = Instant::now();
+    let mut failures = 0usize;
+
+    let solvers: Vec<&Solver> = match year {
+        Some(year) => registry.year(year).collect(),
+        None => registry.iter().collect(),
+    };
+
+    for solver in solvers {
+        let result = read_input(solver, &InputSource::Fixture)
+            .and_then(|input| run_solver(solver, &input, &[1, 2]));
+
+        match result {
+            Ok(result) => {
+                failures += result.parts.iter().filter(|p| p.answer.is_err()).count();
+                print_run(solver, &result);
+            }
+            Err(e) => {
+                failures += 1;
+                println!("{} day {:>2}: error: {e:#}", solver.year, solver.day);
+            }
+        }
+    }
+
+    println!("Process in: {:?}", start.elapsed());
+
+    if failures > 0 {
+        bail!("{failures} part(s) failed");
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Mismatch,
+    Fail(String),
+    Unknown,
+}
+
+impl Verdict {
+    fn label(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "PASS",
+            Verdict::Mismatch => "MISMATCH",
+            Verdict::Fail(_) => "FAIL",
+            Verdict::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+pub struct Verification {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub input: PathBuf,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    pub verdict: Verdict,
+}
+
+// runs each part against every input the manifest lists for it, falling back to
+// the day's fixture (with no expected answer) for parts the manifest doesn't cover
+pub fn verify_solver(solver: &Solver, manifest: Option<&Manifest>) -> Vec<Verification> {
+    let mut checks: Vec<(u8, PathBuf, Option<String>)> = vec![];
+    let mut verifications = vec![];
+    let verification = |part, input, expected, actual, verdict| Verification {
+        year: solver.year,
+        day: solver.day,
+        part,
+        input,
+        expected,
+        actual,
+        verdict,
+    };
+
+    for part in 1..=2 {
+        let answers: Vec<(u8, PathBuf, Option<String>)> = manifest
+            .map(|m| {
+                m.answers_for(solver.day, part)
+                    .map(|a| (part, m.input_path(a), Some(a.expected.clone())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if answers.is_empty() {
+            match fixture_path(solver.year, solver.day) {
+                Ok(path) => checks.push((part, path, None)),
+                Err(e) => verifications.push(verification(
+                    part,
+                    PathBuf::new(),
+                    None,
+                    None,
+                    Verdict::Fail(format!("{e:#}")),
+                )),
+            }
+        } else {
+            checks.extend(answers);
+        }
+    }
+
+    let mut inputs: Vec<&PathBuf> = checks.iter().map(|(_, path, _)| path).collect();
+    inputs.sort();
+    inputs.dedup();
+
+    for input_path in inputs {
+        let solution = Input::from_path(input_path)
+            .and_then(|input| solver.parse(&input))
+            .with_context(|| format!("Could not solve {}", input_path.display()));
+
+        for (part, _, expected) in checks.iter().filter(|(_, path, _)| path == input_path) {
+            let answer = solution.as_ref().map(|solution| match part {
+                1 => solution.part1().map_err(Error::from),
+                _ => solution.part2().map_err(Error::from),
+            });
+
+            let (actual, verdict) = match answer {
+                Ok(Ok(answer)) => {
+                    let actual = answer.to_string();
+                    let verdict = match expected {
+                        Some(expected) if *expected == actual => Verdict::Pass,
+                        Some(_) => Verdict::Mismatch,
+                        None => Verdict::Unknown,
+                    };
+                    (Some(actual), verdict)
+                }
+                Ok(Err(e)) => (None, Verdict::Fail(format!("{e:#}"))),
+                Err(e) => (None, Verdict::Fail(format!("{e:#}"))),
+            };
+
+            verifications.push(verification(
+                *part,
+                input_path.clone(),
+                expected.clone(),
+                actual,
+                verdict,
+            ));
+        }
+    }
+
+    verifications
+}
+
+pub fn print_verifications(verifications: &[Verification]) {
+    println!(
+        "{:<4} {:>3} {:>4}  {:<24} {:>16} {:>16}  status",
+        "year", "day", "part", "input", "expected", "actual"
+    );
+
+    for v in verifications {
+        let input = v
+            .input
+            .file_name()
+            .map_or(v.input.display().to_string(), |f| {
+                f.to_string_lossy().into_owned()
+            });
+
+        println!(
+            "{:<4} {:>3} {:>4}  {:<24} {:>16} {:>16}  {}",
+            v.year,
+            v.day,
+            v.part,
+            input,
+            v.expected.as_deref().unwrap_or("-"),
+            v.actual.as_deref().unwrap_or("-"),
+            v.verdict.label()
+        );
+
+        if let Verdict::Fail(e) = &v.verdict {
+            println!("      {e}");
+        }
+    }
+}
+
+pub fn verify(year: Option<u16>, day: Option<u8>) -> Result<()> {
+    let registry = registry();
+    let start = Instant::now();
+
+    let solvers: Vec<&Solver> = match year {
+        Some(year) => registry.year(year).collect(),
+        None => registry.iter().collect(),
+    };
+
+    let mut manifests: Vec<Manifest> = vec![];
+    let mut verifications = vec![];
+
+    for solver in solvers
+        .into_iter()
+        .filter(|s| day.is_none_or(|day| s.day == day))
+    {
+        if !manifests.iter().any(|m| m.year == solver.year) {
+            manifests.push(Manifest::load(&answers_path(solver.year)?)?);
+        }
+
+        let manifest = manifests.iter().find(|m| m.year == solver.year);
+        verifications.extend(verify_solver(solver, manifest));
+    }
+
+    print_verifications(&verifications);
+
+    let count = |verdict: fn(&Verdict) -> bool| {
+        verifications.iter().filter(|v| verdict(&v.verdict)).count()
+    };
+    let failed = count(|v| matches!(v, Verdict::Fail(_)));
+    let mismatched = count(|v| *v == Verdict::Mismatch);
+
+    println!(
+        "{} passed, {} mismatched, {} failed, {} unknown",
+        count(|v| *v == Verdict::Pass),
+        mismatched,
+        failed,
+        count(|v| *v == Verdict::Unknown)
+    );
+    println!("Process in: {:?}", start.elapsed());
+
+    if failed + mismatched > 0 {
+        bail!("{} answer(s) did not verify", failed + mismatched);
+    }
+
+    Ok(())
+}
+
+pub fn list() {
+    for solver in registry().iter() {
+        println!("{} day {:>2}: {}", solver.year, solver.day, solver.title);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_core::solution::Solution;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static DAY9_PARSES: AtomicUsize = AtomicUsize::new(0);
+
+    // day 9, counting how many times it is parsed
+    struct CountedDay9(cy2023::day9::Day9);
+
+    impl Solution for CountedDay9 {
+        fn parse(input: &Input) -> aoc_core::error::Result<Self> {
+            DAY9_PARSES.fetch_add(1, Ordering::SeqCst);
+            Ok(CountedDay9(cy2023::day9::Day9::parse(input)?))
+        }
+
+        fn part1(&self) -> aoc_core::error::Result<Answer> {
+            self.0.part1()
+        }
+
+        fn part2(&self) -> aoc_core::error::Result<Answer> {
+            self.0.part2()
+        }
+    }
+
+    #[test]
+    fn input_source_from_arg() {
+        assert!(matches!(InputSource::from_arg(None), InputSource::Fixture));
+        assert!(matches!(
+            InputSource::from_arg(Some("-".to_owned())),
+            InputSource::Stdin
+        ));
+        assert!(matches!(
+            InputSource::from_arg(Some("day1.txt".to_owned())),
+            InputSource::Path(p) if p.as_os_str() == "day1.txt"
+        ));
+    }
+
+    #[test]
+    fn run_solver_parts() {
+        let registry = registry();
+        let solver = registry.get(2023, 9).unwrap();
+
+        let result = run_solver(solver, &Input::new("0 3 6 9 12 15"), &[2, 1]).unwrap();
+        let answers: Vec<(u8, String)> = result
+            .parts
+            .into_iter()
+            .map(|p| (p.part, p.answer.unwrap().to_string()))
+            .collect();
+
+        assert_eq!(answers, vec![(2, "-3".to_owned()), (1, "18".to_owned())]);
+    }
+
+    #[test]
+    fn run_solver_reports_parse_errors() {
+        let registry = registry();
+        let solver = registry.get(2023, 9).unwrap();
+
+        let err = run_solver(solver, &Input::new("0 3 6\n1 x 3"), &[1])
+            .err()
+            .unwrap();
+        assert!(format!("{err:#}").contains("day 9: line 2, column 3"));
+    }
+
+    #[test]
+    fn verify_against_manifest() {
+        let manifest = Manifest::parse(
+            r#"year = 2023
+
+[[answers]]
+day = 9
+part = 1
+input = "src/fixtures/day9.txt"
+expected = "1921197370"
+
+[[answers]]
+day = 9
+part = 2
+input = "src/fixtures/day9.txt"
+expected = "1"
+"#,
+            cy2023::answers_path().parent().unwrap(),
+        )
+        .unwrap();
+
+        let registry = registry();
+        let solver = registry.get(2023, 9).unwrap();
+        let verifications = verify_solver(solver, Some(&manifest));
+
+        let verdicts: Vec<&Verdict> = verifications.iter().map(|v| &v.verdict).collect();
+        assert_eq!(verdicts, vec![&Verdict::Pass, &Verdict::Mismatch]);
+        assert_eq!(verifications[1].actual.as_deref(), Some("1124"));
+    }
+
+    #[test]
+    fn verify_shared_inputs_once() {
+        let sample = std::env::temp_dir().join(format!("aoc-verify-{}.txt", std::process::id()));
+        std::fs::write(
+            &sample,
+            "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n",
+        )
+        .unwrap();
+
+        let manifest = Manifest::parse(
+            &format!(
+                r#"year = 2023
+
+[[answers]]
+day = 9
+part = 1
+input = "src/fixtures/day9.txt"
+expected = "1921197370"
+
+[[answers]]
+day = 9
+part = 1
+input = "{}"
+expected = "114"
+
+[[answers]]
+day = 9
+part = 2
+input = "src/fixtures/day9.txt"
+expected = "1124"
+"#,
+                sample.display()
+            ),
+            cy2023::answers_path().parent().unwrap(),
+        )
+        .unwrap();
+
+        let solver = Solver::new::<CountedDay9>(2023, 9, "Mirage Maintenance");
+        let verifications = verify_solver(&solver, Some(&manifest));
+        std::fs::remove_file(&sample).unwrap();
+
+        assert_eq!(verifications.len(), 3);
+        assert!(verifications.iter().all(|v| v.verdict == Verdict::Pass));
+        // once for the fixture both parts share and once for the sample
+        assert_eq!(DAY9_PARSES.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn verify_without_fixture() {
+        let solver = Solver::new::<cy2023::day9::Day9>(1999, 9, "Mirage Maintenance");
+        let verifications = verify_solver(&solver, None);
+
+        assert_eq!(verifications.len(), 2);
+        for v in verifications {
+            assert_eq!(v.expected, None);
+            assert_eq!(
+                v.verdict,
+                Verdict::Fail("No fixtures for year 1999".to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn verify_keeps_error_chain() {
+        let missing = std::env::temp_dir().join("aoc-verify-missing.txt");
+        let manifest = Manifest::parse(
+            &format!(
+                r#"year = 2023
+
+[[answers]]
+day = 9
+part = 1
+input = "{}"
+expected = "114"
+"#,
+                missing.display()
+            ),
+            cy2023::answers_path().parent().unwrap(),
+        )
+        .unwrap();
+
+        let registry = registry();
+        let solver = registry.get(2023, 9).unwrap();
+        let verifications = verify_solver(solver, Some(&manifest));
+
+        let failed = verifications.iter().find(|v| v.input == missing).unwrap();
+        let Verdict::Fail(e) = &failed.verdict else {
+            panic!("verified a missing input");
+        };
+        assert!(e.starts_with(&format!("Could not solve {}: ", missing.display())));
+        assert!(e.contains("No such file"), "{e}");
+    }
+
+    #[test]
+    fn verify_without_manifest() {
+        let registry = registry();
+        let solver = registry.get(2023, 9).unwrap();
+        let verifications = verify_solver(solver, None);
+
+        assert_eq!(verifications.len(), 2);
+        assert!(verifications.iter().all(|v| v.verdict == Verdict::Unknown));
+    }
+
+    #[test]
+    fn fixtures_resolve() {
+        for solver in registry().iter() {
+            let path = fixture_path(solver.year, solver.day).unwrap();
+            assert!(path.exists(), "missing fixture {}", path.display());
+        }
+    }
+}
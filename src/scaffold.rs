@@ -0,0 +1,306 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::runner;
+
+pub fn new_day(year: u16, day: u8, title: Option<String>) -> Result<()> {
+    let title = title.unwrap_or_else(|| format!("Day {day}"));
+    let created = scaffold(&runner::source_dir(year)?, year, day, &title)?;
+
+    for path in created {
+        println!("created {}", path.display());
+    }
+    println!("registered {year} day {day}: {title}");
+
+    Ok(())
+}
+
+// writes `dayN.rs` and an empty fixture into the solver crate's `src` directory,
+// then adds the module and its registration to `lib.rs`; nothing is written if
+// the day already exists
+pub fn scaffold(src_dir: &Path, year: u16, day: u8, title: &str) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        bail!("Day must be between 1 and 25, got {day}");
+    }
+
+    let module_path = src_dir.join(format!("day{day}.rs"));
+    let fixture_path = src_dir.join("fixtures").join(format!("day{day}.txt"));
+    let lib_path = src_dir.join("lib.rs");
+
+    if module_path.exists() {
+        bail!("{} already exists", module_path.display());
+    }
+
+    let lib = fs::read_to_string(&lib_path)
+        .with_context(|| format!("Could not read {}", lib_path.display()))?;
+    let lib = register_day(&add_module(&lib, day)?, day, title)?;
+
+    fs::write(&module_path, module_source(year, day, title))
+        .with_context(|| format!("Could not write {}", module_path.display()))?;
+
+    let mut created = vec![module_path];
+    if !fixture_path.exists() {
+        fs::create_dir_all(src_dir.join("fixtures"))?;
+        fs::write(&fixture_path, "")
+            .with_context(|| format!("Could not write {}", fixture_path.display()))?;
+        created.push(fixture_path);
+    }
+
+    fs::write(&lib_path, lib).with_context(|| format!("Could not write {}", lib_path.display()))?;
+
+    Ok(created)
+}
+
+// inserts `pub mod dayN;` keeping the `pub mod` block in sorted order
+fn add_module(lib: &str, day: u8) -> Result<String> {
+    let name = format!("day{day}");
+    let module = format!("pub mod {name};");
+    let lines: Vec<&str> = lib.lines().collect();
+
+    if lines.contains(&module.as_str()) {
+        bail!("lib.rs already declares day{day}");
+    }
+
+    let mods: Vec<usize> = (0..lines.len())
+        .filter(|&ix| lines[ix].starts_with("pub mod "))
+        .collect();
+    let Some(&last) = mods.last() else {
+        // the first day goes above the imports
+        let at = lines
+            .iter()
+            .position(|line| line.starts_with("use "))
+            .unwrap_or(0);
+        return Ok(insert_line(&lines, at, &format!("{module}\n")));
+    };
+    let at = mods
+        .iter()
+        .copied()
+        .find(|&ix| lines[ix]["pub mod ".len()..].trim_end_matches(';') > name.as_str())
+        .unwrap_or(last + 1);
+
+    Ok(insert_line(&lines, at, &module))
+}
+
+// adds `.register::<DayN>(YEAR, N, "title")` to the registry chain in `register`,
+// ordered by day
+fn register_day(lib: &str, day: u8, title: &str) -> Result<String> {
+    let entry = |ix: usize, line: &str| -> Option<(usize, u8)> {
+        let (_, rest) = line.split_once("(YEAR, ")?;
+        let (n, _) = rest.split_once(',')?;
+        Some((ix, n.trim().parse().ok()?))
+    };
+
+    let mut lines: Vec<String> = lib.lines().map(str::to_owned).collect();
+
+    // a year without any days registers nothing yet
+    if let Some(ix) = lines.iter().position(|line| line == EMPTY_REGISTER) {
+        lines.splice(
+            ix..=ix,
+            [
+                "pub fn register(registry: &mut Registry) {".to_owned(),
+                format!("    registry.register::<day{day}::Day{day}>(YEAR, {day}, {title:?});"),
+                "}".to_owned(),
+            ],
+        );
+        return Ok(lines.join("\n") + "\n");
+    }
+
+    // rustfmt keeps a lone registration on one line; split it back into a chain
+    if let Some(ix) = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("registry.register::<"))
+    {
+        let indent = lines[ix][..lines[ix].len() - lines[ix].trim_start().len()].to_owned();
+        let call = lines[ix].trim_start()["registry".len()..].to_owned();
+        lines.splice(
+            ix..=ix,
+            [format!("{indent}registry"), format!("{indent}    {call}")],
+        );
+    }
+
+    let chain: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.trim_start().starts_with(".register::<"))
+        .filter_map(|(ix, line)| entry(ix, line))
+        .collect();
+
+    let Some(&(last, _)) = chain.last() else {
+        bail!("lib.rs has no `.register::<..>(YEAR, ..)` chain");
+    };
+    if chain.iter().any(|&(_, n)| n == day) {
+        bail!("lib.rs already registers day {day}");
+    }
+
+    let indent = &lines[last][..lines[last].len() - lines[last].trim_start().len()];
+    let registration = format!("{indent}.register::<day{day}::Day{day}>(YEAR, {day}, {title:?})");
+
+    let at = match chain.iter().find(|&&(_, n)| n > day) {
+        Some(&(ix, _)) => ix,
+        None => {
+            // the new day ends the chain, so it takes over the `;`
+            lines[last] = lines[last].trim_end_matches(';').to_owned();
+            last + 1
+        }
+    };
+    let registration = match at > last {
+        true => format!("{registration};"),
+        false => registration,
+    };
+
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    Ok(insert_line(&lines, at, &registration))
+}
+
+const EMPTY_REGISTER: &str = "pub fn register(_registry: &mut Registry) {}";
+
+fn insert_line(lines: &[&str], at: usize, line: &str) -> String {
+    let mut out: Vec<&str> = lines.to_vec();
+    out.insert(at, line);
+    out.join("\n") + "\n"
+}
+
+fn module_source(year: u16, day: u8, title: &str) -> String {
+    TEMPLATE
+        .replace("{year}", &year.to_string())
+        .replace("{day}", &day.to_string())
+        .replace("{title}", title)
+}
+
+const TEMPLATE: &str = r#"// --- Day {day}: {title} ---
+//
+// https://adventofcode.com/{year}/day/{day}
+
+use aoc_core::error::Result;
+use aoc_core::input::Input;
+use aoc_core::solution::{Answer, Solution};
+
+pub struct Day{day} {
+    lines: Vec<String>,
+}
+
+impl Solution for Day{day} {
+    fn parse(input: &Input) -> Result<Self> {
+        Ok(Day{day} {
+            lines: input.lines().map(str::to_owned).collect(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(self.lines.len().into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(0usize.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "";
+
+    #[test]
+    fn day{day}_sample() {
+        let start = std::time::Instant::now();
+        let day = Day{day}::parse(&Input::new(SAMPLE)).unwrap();
+
+        assert_eq!(day.part1().unwrap().to_string(), "0");
+        assert_eq!(day.part2().unwrap().to_string(), "0");
+
+        println!("Process in: {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn day{day}_file() {
+        let start = std::time::Instant::now();
+        let day = Day{day}::parse(&crate::fixture({day}).unwrap()).unwrap();
+
+        println!("part 1: {}", day.part1().unwrap());
+        println!("part 2: {}", day.part2().unwrap());
+
+        println!("Process in: {:?}", start.elapsed());
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = r#"pub mod day1;
+pub mod day2;
+pub mod error;
+
+pub fn register(registry: &mut Registry) {
+    registry
+        .register::<day1::Day1>(YEAR, 1, "Trebuchet?!")
+        .register::<day2::Day2>(YEAR, 2, "Cube Conundrum");
+}
+"#;
+
+    #[test]
+    fn updates_lib() {
+        let lib = register_day(&add_module(LIB, 14).unwrap(), 14, "Parabolic").unwrap();
+
+        assert!(lib.starts_with("pub mod day1;\npub mod day14;\npub mod day2;\n"));
+        assert!(lib.contains(
+            "(YEAR, 2, \"Cube Conundrum\")\n        .register::<day14::Day14>(YEAR, 14, \"Parabolic\");\n}"
+        ));
+
+        let lib = register_day(LIB, 1, "again");
+        assert!(lib.is_err());
+        assert!(add_module(LIB, 2).is_err());
+    }
+
+    #[test]
+    fn first_day_of_a_year() {
+        let lib = "use aoc_core::solution::Registry;\n\npub const YEAR: u16 = 2024;\n\n\
+                   pub fn register(_registry: &mut Registry) {}\n";
+
+        let lib = register_day(&add_module(lib, 1).unwrap(), 1, "Historian Hysteria").unwrap();
+        assert!(lib.starts_with("pub mod day1;\n\nuse aoc_core::solution::Registry;\n"));
+        assert!(lib.contains(
+            "pub fn register(registry: &mut Registry) {\n    \
+             registry.register::<day1::Day1>(YEAR, 1, \"Historian Hysteria\");\n}\n"
+        ));
+
+        let lib = register_day(&add_module(&lib, 2).unwrap(), 2, "Red-Nosed Reports").unwrap();
+        assert!(lib.contains("pub mod day1;\npub mod day2;\n"));
+        assert!(lib.contains(
+            "    registry\n        .register::<day1::Day1>(YEAR, 1, \"Historian Hysteria\")\n        \
+             .register::<day2::Day2>(YEAR, 2, \"Red-Nosed Reports\");\n"
+        ));
+    }
+
+    #[test]
+    fn writes_day() {
+        let dir = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("lib.rs"), LIB).unwrap();
+
+        let created = scaffold(&dir, 2023, 3, "Gear Ratios").unwrap();
+        assert_eq!(
+            created,
+            vec![dir.join("day3.rs"), dir.join("fixtures/day3.txt")]
+        );
+
+        let module = fs::read_to_string(dir.join("day3.rs")).unwrap();
+        assert!(module.starts_with("// --- Day 3: Gear Ratios ---"));
+        assert!(module.contains("impl Solution for Day3 {"));
+        assert!(module.contains("adventofcode.com/2023/day/3"));
+        assert!(module.contains("crate::fixture(3)"));
+
+        let lib = fs::read_to_string(dir.join("lib.rs")).unwrap();
+        assert!(lib.contains("pub mod day2;\npub mod day3;\npub mod error;"));
+        assert!(lib.contains(".register::<day3::Day3>(YEAR, 3, \"Gear Ratios\");"));
+
+        assert!(scaffold(&dir, 2023, 3, "Gear Ratios").is_err());
+        assert!(scaffold(&dir, 2023, 26, "Too late").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}